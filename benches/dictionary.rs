@@ -0,0 +1,92 @@
+use std::{fs::File, io::BufReader};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rswords::word_tree::{self, dawg, normalize::LoadOptions, shared, Lexicon, WordTree};
+
+const WORD_LIST: &str = "assets/scrabble.en.txt";
+
+fn reader() -> BufReader<File> {
+    BufReader::new(File::open(WORD_LIST).expect("Could not read file."))
+}
+
+fn load(c: &mut Criterion) {
+    let tree = word_tree::load_from(reader());
+    let dawg = dawg::load_from(reader());
+    println!(
+        "WordTree: {} nodes, DAWG: {} nodes / {} edges ({} KiB)",
+        tree.words_tree.node_count(),
+        dawg.node_count(),
+        dawg.edge_count(),
+        dawg.heap_size() / 1024
+    );
+
+    let mut group = c.benchmark_group("load");
+    group.sample_size(10);
+    group.bench_function("word_tree", |b| b.iter(|| word_tree::load_from(reader())));
+    group.bench_function("word_tree_parallel", |b| {
+        b.iter(|| shared::load_from_parallel(reader(), &LoadOptions::default()))
+    });
+    group.bench_function("dawg", |b| b.iter(|| dawg::load_from(reader())));
+    group.finish();
+
+    let mut group = c.benchmark_group("contains");
+    let words = ["aardvark", "hello", "zyzzyva", "qwerty", "xylophones"];
+    group.bench_function("word_tree", |b| {
+        b.iter(|| words.iter().filter(|w| tree.contains(w)).count())
+    });
+    group.bench_function("dawg", |b| {
+        b.iter(|| words.iter().filter(|w| dawg.contains(w)).count())
+    });
+    group.finish();
+}
+
+/// The traversal `WordTree::visit` used to do, cloning the letters at every node.
+fn visit_cloning<F>(node: &WordTree, parents: Vec<char>, callback: &mut F)
+where
+    F: FnMut(&Vec<char>),
+{
+    if node.can_be_last_letter {
+        callback(&parents);
+    }
+    for kv in node.next.iter() {
+        let mut new_parents = parents.clone();
+        new_parents.push(*kv.0);
+        visit_cloning(kv.1, new_parents, callback);
+    }
+}
+
+fn traverse(c: &mut Criterion) {
+    let tree = word_tree::load_from(reader()).words_tree;
+
+    let mut group = c.benchmark_group("traverse");
+    group.sample_size(10);
+    group.bench_function("visit_cloning", |b| {
+        b.iter(|| {
+            let mut count = 0;
+            visit_cloning(&tree, vec![], &mut |_| count += 1);
+            count
+        })
+    });
+    group.bench_function("visit", |b| {
+        b.iter(|| {
+            let mut count = 0;
+            tree.visit(&mut |_| count += 1);
+            count
+        })
+    });
+    group.bench_function("words", |b| b.iter(|| tree.words().count()));
+    group.bench_function("words_borrowed", |b| {
+        b.iter(|| {
+            let mut words = tree.words();
+            let mut count = 0;
+            while words.next_word().is_some() {
+                count += 1;
+            }
+            count
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, load, traverse);
+criterion_main!(benches);
@@ -0,0 +1,24 @@
+//! Loads arbitrary bytes as a word list, then as a compiled dictionary.
+//!
+//! ```text
+//! cargo +nightly fuzz run load_from
+//! ```
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rswords::word_tree::{binary::LoadedDictionary, load_from, Lexicon};
+
+fuzz_target!(|data: &[u8]| {
+    let words = load_from(data);
+    for word in words.words() {
+        assert!(!word.is_empty());
+        assert!(words.contains(&word));
+        assert!(words.is_prefix(&word));
+    }
+    if let Ok(dictionary) = LoadedDictionary::from_bytes(data.to_vec()) {
+        for word in dictionary.words_with_prefix("") {
+            assert!(dictionary.contains(&word));
+        }
+    }
+});
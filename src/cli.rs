@@ -0,0 +1,143 @@
+//! Command line tools, run instead of the game when the first argument is a known command.
+//!
+//! ```text
+//! rswords compile <words.txt> <output.rswd>
+//! rswords stats <words.txt|words.rswd>
+//! rswords diff <old> <new>
+//! rswords merge <output> <input>...
+//! rswords subtract <output> <input> <removed>...
+//! ```
+//!
+//! Inputs are word lists or compiled dictionaries, outputs are compiled when their extension is
+//! `rswd` and word lists otherwise.
+
+use std::{
+    error::Error,
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use crate::word_tree::{
+    binary::{self, LoadedDictionary},
+    load_from_with,
+    merge::{self, combine, write_words, Combine},
+    normalize::LoadOptions,
+    Lexicon, PossibleWords,
+};
+
+type CliResult = Result<(), Box<dyn Error>>;
+
+/// Returns `None` when `args` (without the program name) is not a command,
+/// in which case the game should start.
+pub fn run(args: &[String]) -> Option<CliResult> {
+    let (command, args) = args.split_first()?;
+    match command.as_str() {
+        "compile" => Some(compile(args)),
+        "stats" => Some(stats(args)),
+        "diff" => Some(diff(args)),
+        "merge" => Some(merge(args)),
+        "subtract" => Some(subtract(args)),
+        _ => None,
+    }
+}
+
+fn compile(args: &[String]) -> CliResult {
+    let [input, output] = args else {
+        return Err("usage: compile <words.txt> <output.rswd>".into());
+    };
+    let (words, report) =
+        load_from_with(BufReader::new(File::open(input)?), &LoadOptions::default());
+    for rejected in &report.rejected {
+        eprintln!(
+            "{input}:{}: skipped \"{}\" ({:?})",
+            rejected.line_number, rejected.content, rejected.reason
+        );
+    }
+    let mut writer = BufWriter::new(File::create(output)?);
+    binary::write_to(&words, &mut writer)?;
+    writer.flush()?;
+    // Fails early on a file the game would refuse to load.
+    binary::open(output)?;
+    println!(
+        "compiled {input} to {output}: {} words, {} rejected lines",
+        report.accepted,
+        report.rejected.len()
+    );
+    Ok(())
+}
+
+fn stats(args: &[String]) -> CliResult {
+    let [input] = args else {
+        return Err("usage: stats <words.txt|words.rswd>".into());
+    };
+    print!("{}", open_words(input)?.stats());
+    Ok(())
+}
+
+fn diff(args: &[String]) -> CliResult {
+    let [old, new] = args else {
+        return Err("usage: diff <old> <new>".into());
+    };
+    let diff = open_words(old)?.diff(&open_words(new)?);
+    print!("{diff}");
+    eprintln!("{} added, {} removed", diff.added.len(), diff.removed.len());
+    Ok(())
+}
+
+fn merge(args: &[String]) -> CliResult {
+    let [output, inputs @ ..] = args else {
+        return Err("usage: merge <output> <input>...".into());
+    };
+    if inputs.is_empty() {
+        return Err("usage: merge <output> <input>...".into());
+    }
+    let dictionaries = open_all(inputs)?;
+    let lexicons = dictionaries
+        .iter()
+        .map(|d| d as &dyn Lexicon)
+        .collect::<Vec<_>>();
+    write_dictionary(output, &combine(&lexicons, Combine::Union))
+}
+
+fn subtract(args: &[String]) -> CliResult {
+    let [output, input, removed @ ..] = args else {
+        return Err("usage: subtract <output> <input> <removed>...".into());
+    };
+    let base = binary::open(input)?;
+    let dictionaries = open_all(removed)?;
+    let lexicons = dictionaries
+        .iter()
+        .map(|d| d as &dyn Lexicon)
+        .collect::<Vec<_>>();
+    write_dictionary(output, &merge::subtract(&base, &lexicons))
+}
+
+fn open_all(paths: &[String]) -> Result<Vec<LoadedDictionary>, binary::LoadError> {
+    paths.iter().map(binary::open).collect()
+}
+
+/// Writes a compiled dictionary when `output` has the compiled extension, a word list
+/// otherwise.
+fn write_dictionary(output: &str, words: &PossibleWords) -> CliResult {
+    let mut writer = BufWriter::new(File::create(output)?);
+    if Path::new(output)
+        .extension()
+        .is_some_and(|e| e == binary::EXTENSION)
+    {
+        binary::write_to(words, &mut writer)?;
+    } else {
+        write_words(words, &mut writer)?;
+    }
+    writer.flush()?;
+    println!("wrote {output}: {} words", words.words().count());
+    Ok(())
+}
+
+/// Opens a dictionary file as a tree, compiled dictionaries are expanded.
+fn open_words(path: &str) -> Result<PossibleWords, binary::LoadError> {
+    Ok(match binary::open(path)? {
+        LoadedDictionary::Words(words) => words,
+        compiled => combine(&[&compiled], Combine::Union),
+    })
+}
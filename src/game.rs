@@ -1,4 +1,5 @@
 use bevy::{
+    asset::LoadState,
     ecs::schedule::{LogLevel, ScheduleBuildSettings},
     log::{self, Level},
     prelude::*,
@@ -13,19 +14,26 @@ use bevy_mod_picking::{
 };
 use bevy_pancam::*;
 
-use std::{fs::File, io::BufReader};
-
-use self::setup::{create_inventory, GameMarker, MainCamera, TilesInventory};
+use self::{
+    board::{BoardLayout, BoardLayoutHandle, BoardLayoutLoader},
+    dictionary::{
+        AcceptWord, Dictionary, DictionaryLoader, DictionaryMetadata, DictionaryOverlay,
+        DictionaryRegistry, MetadataLoader, SelectedDictionaries, WordsDictionary,
+    },
+    setup::{create_inventory, GameMarker, MainCamera, TilesInventory},
+};
 
-use super::word_tree::load_from;
 use crate::{
     game::{
         self,
         game_ui::{exit_game, ExitGame},
     },
-    word_tree::PossibleWords,
+    word_table::{premium::Premium, TableError, Tile},
+    word_tree::merge::Combine,
 };
 
+mod board;
+mod dictionary;
 mod game_ui;
 mod setup;
 
@@ -34,6 +42,8 @@ pub enum GameState {
     #[default]
     Disabled,
     Loading,
+    /// The dictionary could not be loaded, the game can only be exited.
+    LoadingFailed,
     Playing,
 }
 
@@ -59,6 +69,39 @@ impl Plugin for GamePlugin {
             .add_systems(Update, load_game.run_if(on_event::<StartGame>()));
         app.add_systems(Update, start_game.run_if(in_state(GameState::Loading)));
         app.add_plugins((DefaultPlugins, DefaultPickingPlugins, PanCamPlugin));
+        app.init_asset::<Dictionary>()
+            .init_asset_loader::<DictionaryLoader>()
+            .init_asset::<DictionaryMetadata>()
+            .init_asset_loader::<MetadataLoader>()
+            .init_asset::<BoardLayout>()
+            .init_asset_loader::<BoardLayoutLoader>()
+            .add_systems(
+                Update,
+                (dictionary::reload_dictionary, dictionary::finish_combining),
+            );
+        let assets_dir = bevy::asset::io::file::FileAssetReader::get_base_path().join("assets");
+        let registry = DictionaryRegistry::discover(&assets_dir);
+        app.insert_resource(SelectedDictionaries(registry.default_selection()))
+            .insert_resource(registry)
+            .insert_resource(DictionaryOverlay::open(
+                &assets_dir.join(dictionary::OVERLAY_DIR),
+            ))
+            .add_event::<AcceptWord>()
+            .add_systems(
+                Update,
+                (
+                    dictionary::accept_words,
+                    validate_table.run_if(in_state(GameState::Playing)),
+                )
+                    .chain()
+                    .run_if(on_event::<AcceptWord>()),
+            );
+        app.add_systems(
+            Update,
+            (dictionary_button_system, update_dictionary_buttons)
+                .chain()
+                .run_if(in_state(GameState::Disabled)),
+        );
         app.add_state::<GameState>();
         app.add_event::<StartGame>();
         app.add_event::<game::game_ui::ExitGame>();
@@ -68,6 +111,22 @@ impl Plugin for GamePlugin {
         app.add_systems(OnEnter(GameState::Loading), game::game_ui::game_setup_ui)
             .add_systems(Update, exit_game.run_if(on_event::<ExitGame>()));
         app.add_systems(OnEnter(GameState::Disabled), game::game_ui::game_unsetup_ui);
+        app.add_systems(
+            Update,
+            game::game_ui::update_loading_text.run_if(in_state(GameState::Loading)),
+        );
+        app.add_systems(
+            OnEnter(GameState::LoadingFailed),
+            game::game_ui::show_loading_failed,
+        );
+        app.add_systems(
+            OnEnter(GameState::Playing),
+            game::game_ui::hide_loading_text,
+        );
+        app.add_systems(
+            Update,
+            game::game_ui::button_system.run_if(in_state(GameState::LoadingFailed)),
+        );
         app.add_systems(
             Update,
             game::game_ui::button_system.run_if(in_state(GameState::Loading)),
@@ -77,18 +136,40 @@ impl Plugin for GamePlugin {
             game::game_ui::button_system.run_if(in_state(GameState::Playing)),
         );
         app.add_systems(Update, button_system.run_if(in_state(GameState::Disabled)));
-        app.add_systems(OnExit(GameState::Playing), setup::unsetup);
+        // Exiting is possible while loading, not only while playing.
+        app.add_systems(OnEnter(GameState::Disabled), setup::unsetup);
         app.add_systems(
             OnEnter(GameState::Playing),
-            (create_tiles, create_inventory),
+            (
+                (board::apply_board_layout, create_tiles, validate_table).chain(),
+                create_inventory,
+            ),
         );
         app.add_event::<TileDropped>();
         app.add_systems(
             Update,
-            react_tile_dropped
+            (react_tile_dropped, validate_table, remove_used_premiums)
+                .chain()
                 .run_if(on_event::<TileDropped>())
                 .run_if(in_state(GameState::Playing)),
         );
+        app.add_systems(
+            Update,
+            game::game_ui::update_word_info
+                .after(dictionary::accept_words)
+                .run_if(
+                    resource_exists_and_changed::<HoveredTile>().or_else(on_event::<AcceptWord>()),
+                )
+                .run_if(in_state(GameState::Playing)),
+        );
+        app.add_systems(
+            Update,
+            game::game_ui::show_word_info.run_if(in_state(GameState::Playing)),
+        );
+        app.add_systems(
+            Update,
+            game::game_ui::accept_hovered_words.run_if(in_state(GameState::Playing)),
+        );
         app.add_systems(Update, setup::move_inventory);
         app.configure_sets(
             PreUpdate,
@@ -108,11 +189,45 @@ struct StartGame;
 #[derive(Component)]
 struct MenuMarker;
 
+#[derive(Component)]
+struct PlayButton;
+
+/// Toggles the dictionary at this index of the [`DictionaryRegistry`].
+#[derive(Component)]
+struct DictionaryButton(usize);
+
+/// Switches how several selected dictionaries are combined.
+#[derive(Component)]
+struct CombineButton;
+
 pub fn load_game(mut game_state: ResMut<NextState<GameState>>) {
     game_state.set(GameState::Loading);
 }
-pub fn start_game(mut game_state: ResMut<NextState<GameState>>) {
-    game_state.set(GameState::Playing);
+pub fn start_game(
+    mut game_state: ResMut<NextState<GameState>>,
+    dictionary: Option<ResMut<WordsDictionary>>,
+    layout: Option<Res<BoardLayoutHandle>>,
+    asset_server: Res<AssetServer>,
+    dictionaries: Res<Assets<Dictionary>>,
+) {
+    let Some(mut dictionary) = dictionary else {
+        return;
+    };
+    // A missing layout is not fatal, see `board::apply_board_layout`.
+    if !layout.is_some_and(|layout| layout.is_settled(&asset_server)) {
+        return;
+    }
+    match dictionary.load_state(&asset_server) {
+        LoadState::Loaded => {
+            if dictionary.is_ready() {
+                game_state.set(GameState::Playing);
+            } else if !dictionary.is_combining() {
+                dictionary.combine_sources(&dictionaries);
+            }
+        }
+        LoadState::Failed => game_state.set(GameState::LoadingFailed),
+        LoadState::NotLoaded | LoadState::Loading => {}
+    }
 }
 
 const NORMAL_BUTTON: Color = Color::rgb(0.15, 0.15, 0.15);
@@ -127,7 +242,7 @@ fn button_system(
             &mut BorderColor,
             &Children,
         ),
-        (Changed<Interaction>, With<Button>),
+        (Changed<Interaction>, With<PlayButton>),
     >,
     mut text_query: Query<&mut Text>,
     mut start_game: EventWriter<StartGame>,
@@ -155,13 +270,95 @@ fn button_system(
     }
 }
 
+fn dictionary_button_system(
+    q_dictionary_buttons: Query<(&Interaction, &DictionaryButton), Changed<Interaction>>,
+    q_combine_buttons: Query<&Interaction, (Changed<Interaction>, With<CombineButton>)>,
+    registry: Res<DictionaryRegistry>,
+    mut selected: ResMut<SelectedDictionaries>,
+) {
+    for (interaction, button) in q_dictionary_buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            selected.0.toggle(&registry.entries[button.0].path);
+        }
+    }
+    for interaction in q_combine_buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            selected.0.combine = match selected.0.combine {
+                Combine::Union => Combine::Intersection,
+                Combine::Intersection => Combine::Union,
+            };
+        }
+    }
+}
+
+fn update_dictionary_buttons(
+    mut q_dictionary_buttons: Query<(&DictionaryButton, &mut BackgroundColor, &Children)>,
+    q_combine_buttons: Query<&Children, With<CombineButton>>,
+    mut text_query: Query<&mut Text>,
+    registry: Res<DictionaryRegistry>,
+    selected: Res<SelectedDictionaries>,
+) {
+    for (button, mut color, children) in q_dictionary_buttons.iter_mut() {
+        let entry = &registry.entries[button.0];
+        let is_selected = selected.0.paths.contains(&entry.path);
+        *color = if is_selected {
+            PRESSED_BUTTON.into()
+        } else {
+            NORMAL_BUTTON.into()
+        };
+        if let Ok(mut text) = text_query.get_mut(children[0]) {
+            let check = if is_selected { "x" } else { " " };
+            text.sections[0].value = format!("[{check}] {}", entry.name);
+        }
+    }
+    for children in q_combine_buttons.iter() {
+        if let Ok(mut text) = text_query.get_mut(children[0]) {
+            text.sections[0].value = match selected.0.combine {
+                Combine::Union => "Any selected list".to_string(),
+                Combine::Intersection => "All selected lists".to_string(),
+            };
+        }
+    }
+}
+
 fn unsetup_ui(mut commands: Commands, q_menus: Query<Entity, With<MenuMarker>>) {
     for e in q_menus.iter() {
         commands.entity(e).despawn_recursive();
     }
 }
 
-fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+/// Spawns a button whose text is set by [`update_dictionary_buttons`].
+fn spawn_toggle_button(parent: &mut ChildBuilder, marker: impl Component, margin_bottom: f32) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    padding: UiRect::all(Val::Px(5.0)),
+                    margin: UiRect::bottom(Val::Px(margin_bottom)),
+                    ..default()
+                },
+                background_color: NORMAL_BUTTON.into(),
+                ..default()
+            },
+            marker,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: default(),
+                    font_size: 24.0,
+                    color: Color::rgb(0.9, 0.9, 0.9),
+                },
+            ));
+        });
+}
+
+fn setup_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    registry: Res<DictionaryRegistry>,
+) {
     // ui camera
     commands.spawn((
         Camera2dBundle {
@@ -179,6 +376,7 @@ fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                 style: Style {
                     width: Val::Percent(100.0),
                     height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
                     align_items: AlignItems::Center,
                     justify_content: JustifyContent::Center,
                     ..default()
@@ -188,22 +386,29 @@ fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
             MenuMarker,
         ))
         .with_children(|parent| {
+            for i in 0..registry.entries.len() {
+                spawn_toggle_button(parent, DictionaryButton(i), 5.0);
+            }
+            spawn_toggle_button(parent, CombineButton, 20.0);
             parent
-                .spawn(ButtonBundle {
-                    style: Style {
-                        width: Val::Px(150.0),
-                        height: Val::Px(65.0),
-                        border: UiRect::all(Val::Px(5.0)),
-                        // horizontally center child text
-                        justify_content: JustifyContent::Center,
-                        // vertically center child text
-                        align_items: AlignItems::Center,
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(150.0),
+                            height: Val::Px(65.0),
+                            border: UiRect::all(Val::Px(5.0)),
+                            // horizontally center child text
+                            justify_content: JustifyContent::Center,
+                            // vertically center child text
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        border_color: BorderColor(Color::BLACK),
+                        background_color: NORMAL_BUTTON.into(),
                         ..default()
                     },
-                    border_color: BorderColor(Color::BLACK),
-                    background_color: NORMAL_BUTTON.into(),
-                    ..default()
-                })
+                    PlayButton,
+                ))
                 .with_children(|parent| {
                     parent.spawn(TextBundle::from_section(
                         "Button",
@@ -216,9 +421,6 @@ fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                 });
         });
 }
-#[derive(Resource)]
-pub struct WordsDictionary(PossibleWords);
-
 #[derive(Component, Clone)]
 pub struct TilePos(IVec2);
 
@@ -238,6 +440,43 @@ impl TilePos {
     }
 }
 
+/// Table position of the tile under the pointer, while playing.
+#[derive(Resource, Default)]
+pub struct HoveredTile(pub Option<IVec2>);
+
+/// Square behind the letter of a tile, its color tells whether the tile is part of an
+/// invalid word.
+#[derive(Component)]
+struct TileBackground;
+
+const TILE_COLOR: Color = Color::hsl(120.0, 1.0, 0.5);
+const INVALID_TILE_COLOR: Color = Color::hsl(0.0, 1.0, 0.5);
+const ISLAND_TILE_COLOR: Color = Color::hsl(40.0, 1.0, 0.5);
+
+/// Square under the tiles at this table position, despawned once a tile used its premium.
+#[derive(Component)]
+struct PremiumSquare(IVec2);
+
+fn premium_color(premium: &Premium) -> Color {
+    match premium {
+        Premium::DoubleLetter => Color::hsl(200.0, 0.6, 0.75),
+        Premium::TripleLetter => Color::hsl(220.0, 0.8, 0.5),
+        Premium::DoubleWord => Color::hsl(340.0, 0.6, 0.75),
+        Premium::TripleWord => Color::hsl(0.0, 0.8, 0.45),
+        Premium::Custom { .. } => Color::hsl(280.0, 0.5, 0.6),
+    }
+}
+
+/// A tile taken off the table, its [`TilePos`] is removed until it is placed again.
+#[derive(Component)]
+struct InInventory(Tile);
+
+/// Tiles, wherever they are.
+type OnTableOrInventory = Or<(With<TilePos>, With<InInventory>)>;
+
+/// Local position of the tiles put back in the inventory after a refused drop.
+const INVENTORY_SLOT: Vec3 = Vec3::new(100f32, 100f32, 0f32);
+
 #[derive(Event)]
 pub struct TileDropped {
     pub listener: Entity,
@@ -263,7 +502,38 @@ fn create_tiles(
         color: Color::WHITE,
     };
     let text_alignment = TextAlignment::Center;
-    for kv in &table.0.tiles {
+    let premium_text_style = TextStyle {
+        font_size: 24.0,
+        ..text_style.clone()
+    };
+    for (position, premium) in table.0.premiums() {
+        // Below the tile backgrounds.
+        let square_transform =
+            Transform::from_translation(TilePos::from(position).to_local_pos().extend(-2f32));
+        commands
+            .spawn((
+                PremiumSquare(*position),
+                GameMarker,
+                LAYER_WORLD,
+                MaterialMesh2dBundle {
+                    mesh: meshes.add(Mesh::from(shape::Quad::default())).into(),
+                    transform: square_transform.with_scale(Vec3::splat(58f32)),
+                    material: materials.add(ColorMaterial::from(premium_color(premium))),
+                    ..Default::default()
+                },
+            ))
+            .with_children(|parent| {
+                parent.spawn(Text2dBundle {
+                    text: Text::from_section(premium.to_string(), premium_text_style.clone())
+                        .with_alignment(text_alignment),
+                    // Undo the scale of the square.
+                    transform: Transform::from_translation(Vec3::Z * 0.5)
+                        .with_scale(Vec3::splat(1f32 / 58f32)),
+                    ..default()
+                });
+            });
+    }
+    for kv in table.0.tiles() {
         let tile_transform =
             Transform::from_translation(TilePos::from(kv.0).to_local_pos().extend(0f32));
         commands
@@ -339,6 +609,23 @@ fn create_tiles(
                         tracing::event!(Level::INFO, "(input) stop drag",);
                     },
                 ),
+                On::<Pointer<Over>>::run(
+                    |event: ListenerMut<Pointer<Over>>,
+                     q_tile_pos: Query<&TilePos>,
+                     mut hovered: ResMut<HoveredTile>| {
+                        hovered.0 = q_tile_pos.get(event.listener()).ok().map(|p| p.0);
+                    },
+                ),
+                On::<Pointer<Out>>::run(
+                    |event: ListenerMut<Pointer<Out>>,
+                     q_tile_pos: Query<&TilePos>,
+                     mut hovered: ResMut<HoveredTile>| {
+                        // Over of the next tile may come first.
+                        if q_tile_pos.get(event.listener()).ok().map(|p| p.0) == hovered.0 {
+                            hovered.0 = None;
+                        }
+                    },
+                ),
             ))
             .with_children(|parent| {
                 parent.spawn((
@@ -347,11 +634,12 @@ fn create_tiles(
                         mesh: meshes.add(Mesh::from(shape::Quad::default())).into(),
                         transform: Transform::from_translation(Vec3::NEG_Z)
                             .with_scale(Vec3::splat(60f32)),
-                        material: materials.add(ColorMaterial::from(Color::hsl(120.0, 1.0, 0.5))),
+                        material: materials.add(ColorMaterial::from(TILE_COLOR)),
                         ..Default::default()
                     },
                     PickableBundle::default(),
                     RaycastPickable,
+                    TileBackground,
                 ));
             });
     }
@@ -363,20 +651,22 @@ fn react_tile_dropped(
     camera_world: Query<&Transform, With<MainCamera>>,
     camera_ui: Query<&OrthographicProjection, With<MainCamera>>,
     mut transforms: Query<
-        (Entity, &mut Transform, &mut TilePos),
+        (
+            Entity,
+            &mut Transform,
+            Option<&TilePos>,
+            Option<&InInventory>,
+        ),
         (Without<MainCamera>, Without<TilesInventory>),
     >,
-    mut q_inventory: Query<
-        (Entity, &GlobalTransform, &mut Transform, &TilesInventory),
-        Without<MainCamera>,
-    >,
+    q_inventory: Query<(Entity, &Transform, &TilesInventory), Without<MainCamera>>,
     mut tile_dropped_event: EventReader<TileDropped>,
 ) {
     for tile_dropped in tile_dropped_event.read() {
-        let Ok((tile_entity, mut transform, mut tile_pos)) =
+        let Ok((tile_entity, mut transform, tile_pos, in_inventory)) =
             transforms.get_mut(tile_dropped.listener)
         else {
-            return;
+            continue;
         };
         let camera_world_projection = camera_ui.single();
         let camera_transform = camera_world.single();
@@ -393,39 +683,85 @@ fn react_tile_dropped(
 
         let mut layer = LAYER_WORLD;
         let mut table = q_table.single_mut();
-        if let Ok((i_entity, i_global_transform, i_transform, i_inventory)) =
-            q_inventory.get_single()
-        {
+        let inventory = q_inventory.get_single().ok();
+        let in_inventory_rect = inventory.is_some_and(|(_, i_transform, i_inventory)| {
             let global_pos_inventory = i_transform.transform_point(Vec3::ZERO);
-
             let range_x =
                 global_pos_inventory.x..(global_pos_inventory.x + i_inventory.screen_rect.width());
             let range_y =
                 global_pos_inventory.y..(global_pos_inventory.y + i_inventory.screen_rect.height());
-            dbg!(&range_y);
-            if range_x.contains(&transform.translation.x)
-                && range_y.contains(&transform.translation.y)
-            {
-                dbg!("inside");
+            range_x.contains(&transform.translation.x) && range_y.contains(&transform.translation.y)
+        });
+        // Dropped in the inventory, or refused on the table after being taken from there.
+        let mut to_inventory = in_inventory_rect || in_inventory.is_some();
+        if in_inventory_rect {
+            if let Some(tile_pos) = tile_pos {
+                match table.0.take_tile(tile_pos.0) {
+                    Ok((tile, changes)) => {
+                        tracing::event!(Level::DEBUG, "{changes:?}");
+                        commands
+                            .entity(tile_entity)
+                            .remove::<TilePos>()
+                            .insert(InInventory(tile));
+                    }
+                    Err(e) => {
+                        tracing::event!(Level::ERROR, "tile out of sync with the table: {e}");
+                        to_inventory = false;
+                        transform.translation = tile_pos.to_local_pos().extend(0f32);
+                    }
+                }
+            }
+        } else {
+            let possible_new_tile_pos = TilePos::from_world_pos(&transform.translation.xy());
+            let result = match (tile_pos, in_inventory) {
+                (Some(tile_pos), _) => table.0.move_tile(tile_pos.0, possible_new_tile_pos.0),
+                (None, Some(InInventory(tile))) => {
+                    table.0.place_tile(possible_new_tile_pos.0, tile.clone())
+                }
+                (None, None) => {
+                    tracing::event!(
+                        Level::ERROR,
+                        "dropped tile is neither on the table nor in the inventory"
+                    );
+                    continue;
+                }
+            };
+            match result {
+                Ok(changes) => {
+                    tracing::event!(Level::DEBUG, "{changes:?}");
+                    to_inventory = false;
+                    commands
+                        .entity(tile_entity)
+                        .remove::<InInventory>()
+                        .insert(possible_new_tile_pos);
+                }
+                Err(e @ (TableError::Occupied(_) | TableError::OutOfBounds(_))) => {
+                    tracing::event!(Level::DEBUG, "{e}");
+                    if let Some(tile_pos) = tile_pos {
+                        transform.translation = tile_pos.to_local_pos().extend(0f32);
+                    }
+                }
+                // The entity has a position which the table has no tile at.
+                Err(e @ TableError::EmptySource(_)) => {
+                    tracing::event!(Level::ERROR, "tile out of sync with the table: {e}");
+                    if let Some(tile_pos) = tile_pos {
+                        transform.translation = tile_pos.to_local_pos().extend(0f32);
+                    }
+                }
+            }
+        }
+        if to_inventory {
+            if let Some((i_entity, i_transform, _)) = inventory {
                 commands.entity(tile_entity).set_parent(i_entity);
-                transform.translation -= i_transform.translation;
-                //transform.translation = i_global_transform.transform_point(transform.translation);
+                if in_inventory_rect {
+                    transform.translation -= i_transform.translation;
+                } else {
+                    // Refused drop of a tile from the inventory.
+                    transform.translation = INVENTORY_SLOT;
+                }
                 layer = LAYER_INVENTORY;
-                table.0.tiles.remove(&tile_pos.0);
             }
         }
-        let possible_new_tile_pos = TilePos::from_world_pos(&transform.translation.xy());
-        if table.0.tiles.contains_key(&possible_new_tile_pos.0) {
-            let original_position = &TilePos::to_local_pos(&tile_pos);
-            transform.translation = original_position.extend(0f32);
-        } else {
-            commands
-                .entity(tile_dropped.listener)
-                .insert(possible_new_tile_pos.clone());
-            let copy = table.0.tiles[&tile_pos.0].clone();
-            table.0.tiles.remove(&tile_pos.0);
-            table.0.tiles.insert(possible_new_tile_pos.0, copy);
-        }
 
         tracing::event!(
             Level::INFO,
@@ -441,3 +777,59 @@ fn react_tile_dropped(
         transform.translation.z = 0f32;
     }
 }
+
+/// Tints the tiles of invalid words with [`INVALID_TILE_COLOR`], and the tiles cut off from
+/// the rest of the table with [`ISLAND_TILE_COLOR`].
+fn validate_table(
+    q_table: Query<&setup::Table>,
+    words_dictionary: Res<WordsDictionary>,
+    dictionaries: Res<Assets<Dictionary>>,
+    overlay: Res<DictionaryOverlay>,
+    q_tiles: Query<(Option<&TilePos>, &Children), OnTableOrInventory>,
+    q_backgrounds: Query<&Handle<ColorMaterial>, With<TileBackground>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let (Ok(table), Some(dictionary)) = (
+        q_table.get_single(),
+        words_dictionary.get(&dictionaries, &overlay),
+    ) else {
+        return;
+    };
+    let validation = table.0.validate(&dictionary);
+    for word in &validation.invalid {
+        tracing::event!(Level::INFO, "invalid word {}", word.word);
+    }
+    if validation.is_complete() {
+        tracing::event!(Level::INFO, "board complete");
+    }
+    let island_tiles = validation.islands().concat();
+    for (tile_pos, children) in q_tiles.iter() {
+        // Tiles in the inventory have no position.
+        let color = match tile_pos {
+            Some(p) if validation.invalid_tiles.contains(&p.0) => INVALID_TILE_COLOR,
+            Some(p) if island_tiles.contains(&p.0) => ISLAND_TILE_COLOR,
+            _ => TILE_COLOR,
+        };
+        for handle in q_backgrounds.iter_many(children) {
+            if let Some(material) = materials.get_mut(handle) {
+                material.color = color;
+            }
+        }
+    }
+}
+
+/// Despawns the squares of the premiums used by a tile.
+fn remove_used_premiums(
+    mut commands: Commands,
+    q_table: Query<&setup::Table>,
+    q_squares: Query<(Entity, &PremiumSquare)>,
+) {
+    let Ok(table) = q_table.get_single() else {
+        return;
+    };
+    for (entity, square) in q_squares.iter() {
+        if !table.0.premiums().contains_key(&square.0) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
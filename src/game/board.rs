@@ -0,0 +1,122 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext, LoadState},
+    prelude::*,
+    utils::BoxedFuture,
+};
+
+use crate::word_table::{
+    premium::{self, LayoutError, PremiumLayout},
+    shape::BoardShape,
+};
+
+use super::setup::Table;
+
+/// Premium squares of the table, relative to the `assets` folder.
+pub const BOARD_LAYOUT: &str = "boards/scrabble.board";
+
+/// Premium squares and bounds of a board, see [`premium`].
+#[derive(Asset, TypePath)]
+pub struct BoardLayout(pub PremiumLayout);
+
+#[derive(Default)]
+pub struct BoardLayoutLoader;
+
+#[derive(Debug)]
+pub enum BoardLayoutLoaderError {
+    Io(std::io::Error),
+    Utf8(std::str::Utf8Error),
+    Layout(LayoutError),
+}
+
+impl std::fmt::Display for BoardLayoutLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BoardLayoutLoaderError::Io(e) => write!(f, "could not read board layout: {e}"),
+            BoardLayoutLoaderError::Utf8(e) => write!(f, "board layout is not text: {e}"),
+            BoardLayoutLoaderError::Layout(e) => write!(f, "invalid board layout: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for BoardLayoutLoaderError {}
+
+impl From<std::io::Error> for BoardLayoutLoaderError {
+    fn from(value: std::io::Error) -> Self {
+        BoardLayoutLoaderError::Io(value)
+    }
+}
+
+impl From<std::str::Utf8Error> for BoardLayoutLoaderError {
+    fn from(value: std::str::Utf8Error) -> Self {
+        BoardLayoutLoaderError::Utf8(value)
+    }
+}
+
+impl From<LayoutError> for BoardLayoutLoaderError {
+    fn from(value: LayoutError) -> Self {
+        BoardLayoutLoaderError::Layout(value)
+    }
+}
+
+impl AssetLoader for BoardLayoutLoader {
+    type Asset = BoardLayout;
+    type Settings = ();
+    type Error = BoardLayoutLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<BoardLayout, BoardLayoutLoaderError>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            let layout = PremiumLayout::parse(std::str::from_utf8(&bytes)?)?;
+            Ok(BoardLayout(layout))
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[premium::EXTENSION]
+    }
+}
+
+/// Layout of the current game, loaded with the dictionary.
+#[derive(Resource)]
+pub struct BoardLayoutHandle(pub Handle<BoardLayout>);
+
+impl BoardLayoutHandle {
+    /// Whether the game can start: the layout is loaded, or could not be and the table stays
+    /// unbounded without premium squares.
+    pub fn is_settled(&self, asset_server: &AssetServer) -> bool {
+        matches!(
+            asset_server.load_state(self.0.id()),
+            LoadState::Loaded | LoadState::Failed
+        )
+    }
+}
+
+/// Bounds the table by the loaded layout, centered on the origin, and adds its premium
+/// squares.
+pub(super) fn apply_board_layout(
+    handle: Option<Res<BoardLayoutHandle>>,
+    layouts: Res<Assets<BoardLayout>>,
+    mut q_table: Query<&mut Table>,
+) {
+    let Ok(mut table) = q_table.get_single_mut() else {
+        return;
+    };
+    let Some(BoardLayout(layout)) = handle.and_then(|handle| layouts.get(&handle.0)) else {
+        warn!("no board layout, the table is unbounded");
+        return;
+    };
+    if let Some(bounds) = layout.centered_bounds() {
+        let tiles = table.0.tiles().clone();
+        match crate::word_table::Table::with_shape(tiles, BoardShape::Rect(bounds)) {
+            Ok(bounded) => table.0 = bounded,
+            Err(e) => warn!("table left unbounded, a starting tile is off the board: {e}"),
+        }
+    }
+    table.0.set_premiums(layout.centered());
+}
@@ -0,0 +1,581 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext, LoadState},
+    prelude::*,
+    tasks::{block_on, AsyncComputeTaskPool, Task},
+    utils::BoxedFuture,
+};
+
+use crate::word_tree::{
+    binary::{self, FormatError, LoadedDictionary},
+    fuzzy::Suggestion,
+    merge::{self, Combine},
+    metadata::{self, WordInfo, WordMetadata},
+    normalize::LoadOptions,
+    overlay::{Layered, WordOverlay},
+    shared::{SharedDictionary, SharedLayered},
+    Lexicon,
+};
+
+/// Dictionary selected when it exists, relative to the `assets` folder.
+const DEFAULT_DICTIONARY: &str = "scrabble.en.txt";
+/// Folder of the allow and deny lists, relative to the `assets` folder.
+pub const OVERLAY_DIR: &str = "overlays";
+
+/// A word list, loaded from a `.txt` file or a compiled `.rswd` dictionary.
+///
+/// Clone the inner [`SharedDictionary`] to query it from tasks.
+#[derive(Asset, TypePath)]
+pub struct Dictionary(pub SharedDictionary);
+
+impl Lexicon for Dictionary {
+    fn contains(&self, word: &str) -> bool {
+        self.0.contains(word)
+    }
+
+    fn is_prefix(&self, prefix: &str) -> bool {
+        self.0.is_prefix(prefix)
+    }
+
+    fn words_with_prefix(&self, prefix: &str) -> Vec<String> {
+        self.0.words_with_prefix(prefix)
+    }
+
+    fn suggestions(&self, word: &str, max_distance: usize) -> Vec<Suggestion> {
+        self.0.suggestions(word, max_distance)
+    }
+}
+
+#[derive(Default)]
+pub struct DictionaryLoader;
+
+#[derive(Debug)]
+pub enum DictionaryLoaderError {
+    Io(std::io::Error),
+    Format(FormatError),
+}
+
+impl std::fmt::Display for DictionaryLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DictionaryLoaderError::Io(e) => write!(f, "could not read dictionary: {e}"),
+            DictionaryLoaderError::Format(e) => write!(f, "invalid compiled dictionary: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for DictionaryLoaderError {}
+
+impl From<std::io::Error> for DictionaryLoaderError {
+    fn from(value: std::io::Error) -> Self {
+        DictionaryLoaderError::Io(value)
+    }
+}
+
+impl From<FormatError> for DictionaryLoaderError {
+    fn from(value: FormatError) -> Self {
+        DictionaryLoaderError::Format(value)
+    }
+}
+
+impl AssetLoader for DictionaryLoader {
+    type Asset = Dictionary;
+    type Settings = ();
+    type Error = DictionaryLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Dictionary, DictionaryLoaderError>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            // Word lists are built on the compute pool, not on new threads.
+            let pool = AsyncComputeTaskPool::get();
+            let workers = NonZeroUsize::new(pool.thread_num()).unwrap_or(NonZeroUsize::MIN);
+            let (dictionary, report) = LoadedDictionary::from_bytes_parallel_with(
+                bytes,
+                &LoadOptions::default(),
+                workers,
+                |jobs| {
+                    pool.scope(|scope| {
+                        for job in jobs {
+                            scope.spawn(async move { job.run() });
+                        }
+                    })
+                },
+            )?;
+            for rejected in &report.rejected {
+                warn!(
+                    "{}:{}: skipped \"{}\" ({:?})",
+                    load_context.path().display(),
+                    rejected.line_number,
+                    rejected.content,
+                    rejected.reason
+                );
+            }
+            Ok(Dictionary(dictionary.into()))
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["txt", crate::word_tree::binary::EXTENSION]
+    }
+}
+
+/// Definitions and other information about the words of a [`Dictionary`], see [`metadata`].
+#[derive(Asset, TypePath)]
+pub struct DictionaryMetadata(pub WordMetadata);
+
+#[derive(Default)]
+pub struct MetadataLoader;
+
+impl AssetLoader for MetadataLoader {
+    type Asset = DictionaryMetadata;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<DictionaryMetadata, std::io::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            let (metadata, report) =
+                WordMetadata::load_from(bytes.as_slice(), &LoadOptions::default());
+            for rejected in &report.rejected {
+                warn!(
+                    "{}:{}: skipped \"{}\" ({:?})",
+                    load_context.path().display(),
+                    rejected.line_number,
+                    rejected.content,
+                    rejected.reason
+                );
+            }
+            Ok(DictionaryMetadata(metadata))
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[metadata::EXTENSION]
+    }
+}
+
+/// A word list found in the `assets` folder.
+pub struct DictionaryEntry {
+    /// File name without extension, shown in the menu.
+    pub name: String,
+    /// Asset path, relative to the `assets` folder.
+    pub path: String,
+    /// Asset path of the metadata sidecar (`<name>.tsv`), when there is one.
+    pub metadata: Option<String>,
+}
+
+/// Dictionaries which can be selected from the main menu.
+#[derive(Resource, Default)]
+pub struct DictionaryRegistry {
+    pub entries: Vec<DictionaryEntry>,
+}
+
+impl DictionaryRegistry {
+    /// Lists the `.txt` and compiled dictionaries at the root of `dir`, sorted by path.
+    pub fn discover(dir: &Path) -> Self {
+        let mut entries = vec![];
+        let Ok(read_dir) = std::fs::read_dir(dir) else {
+            warn!("could not list dictionaries in {}", dir.display());
+            return Self { entries };
+        };
+        for file in read_dir.flatten() {
+            let path = file.path();
+            let is_dictionary = path
+                .extension()
+                .is_some_and(|e| e == "txt" || e == binary::EXTENSION);
+            if !path.is_file() || !is_dictionary {
+                continue;
+            }
+            let (Some(name), Some(file_name)) = (
+                path.file_stem().and_then(|n| n.to_str()),
+                path.file_name().and_then(|n| n.to_str()),
+            ) else {
+                continue;
+            };
+            let metadata = format!("{name}.{}", metadata::EXTENSION);
+            entries.push(DictionaryEntry {
+                name: name.to_string(),
+                path: file_name.to_string(),
+                metadata: dir.join(&metadata).is_file().then_some(metadata),
+            });
+        }
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        Self { entries }
+    }
+
+    /// Selects [`DEFAULT_DICTIONARY`], or the first dictionary when it is missing.
+    pub fn default_selection(&self) -> DictionarySelection {
+        let entry = self
+            .entries
+            .iter()
+            .find(|e| e.path == DEFAULT_DICTIONARY)
+            .or(self.entries.first());
+        DictionarySelection {
+            paths: entry.map(|e| e.path.clone()).into_iter().collect(),
+            combine: Combine::default(),
+        }
+    }
+}
+
+/// Dictionaries a game is played with.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DictionarySelection {
+    /// Asset paths, relative to the `assets` folder.
+    pub paths: Vec<String>,
+    /// How the dictionaries are combined when there are several.
+    pub combine: Combine,
+}
+
+impl DictionarySelection {
+    pub fn toggle(&mut self, path: &str) {
+        if let Some(i) = self.paths.iter().position(|p| p == path) {
+            self.paths.remove(i);
+        } else {
+            self.paths.push(path.to_string());
+        }
+    }
+}
+
+impl std::fmt::Display for DictionarySelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let separator = match self.combine {
+            Combine::Union => " + ",
+            Combine::Intersection => " & ",
+        };
+        write!(f, "{}", self.paths.join(separator))
+    }
+}
+
+/// Main menu selection, used by the next game.
+#[derive(Resource, Default)]
+pub struct SelectedDictionaries(pub DictionarySelection);
+
+/// Dictionary of the current game.
+#[derive(Resource)]
+pub struct WordsDictionary {
+    /// Dictionaries the game was started with.
+    pub selection: DictionarySelection,
+    /// One handle per selected dictionary.
+    sources: Vec<Handle<Dictionary>>,
+    /// The only source, or the combination of all sources, once they are loaded.
+    handle: Option<Handle<Dictionary>>,
+    /// Combination of the sources being built, see [`WordsDictionary::combine_sources`].
+    combining: Option<Task<Dictionary>>,
+    /// Sidecars of the selected dictionaries which have one, not waited for.
+    metadata: Vec<Handle<DictionaryMetadata>>,
+}
+
+impl WordsDictionary {
+    pub fn load(
+        selection: DictionarySelection,
+        registry: &DictionaryRegistry,
+        asset_server: &AssetServer,
+    ) -> Self {
+        let sources = selection
+            .paths
+            .iter()
+            .map(|path| asset_server.load(path.clone()))
+            .collect();
+        let metadata = registry
+            .entries
+            .iter()
+            .filter(|entry| selection.paths.contains(&entry.path))
+            .filter_map(|entry| entry.metadata.clone())
+            .map(|path| asset_server.load(path))
+            .collect();
+        Self {
+            selection,
+            sources,
+            handle: None,
+            combining: None,
+            metadata,
+        }
+    }
+
+    /// `Failed` if any source failed or nothing is selected, `Loaded` once all sources are.
+    pub fn load_state(&self, asset_server: &AssetServer) -> LoadState {
+        if self.sources.is_empty() {
+            return LoadState::Failed;
+        }
+        let states = self
+            .sources
+            .iter()
+            .map(|handle| asset_server.load_state(handle.id()))
+            .collect::<Vec<LoadState>>();
+        if states.contains(&LoadState::Failed) {
+            LoadState::Failed
+        } else if states.iter().all(|s| *s == LoadState::Loaded) {
+            LoadState::Loaded
+        } else {
+            LoadState::Loading
+        }
+    }
+
+    /// Number of loaded sources, and of selected sources.
+    ///
+    /// Bevy does not report progress within a file, a large word list counts once loaded.
+    pub fn progress(&self, asset_server: &AssetServer) -> (usize, usize) {
+        let loaded = self
+            .sources
+            .iter()
+            .filter(|handle| asset_server.load_state(handle.id()) == LoadState::Loaded)
+            .count();
+        (loaded, self.sources.len())
+    }
+
+    /// Builds the game dictionary from the loaded sources, again if it was already built.
+    ///
+    /// Several sources are combined on the `AsyncComputeTaskPool`, the previous dictionary
+    /// is used until [`finish_combining`] replaces it.
+    pub fn combine_sources(&mut self, dictionaries: &Assets<Dictionary>) {
+        if let [source] = self.sources.as_slice() {
+            self.handle = Some(source.clone());
+            return;
+        }
+        let Some(sources) = self
+            .sources
+            .iter()
+            .map(|handle| dictionaries.get(handle).map(|d| d.0.clone()))
+            .collect::<Option<Vec<SharedDictionary>>>()
+        else {
+            return;
+        };
+        let combine = self.selection.combine;
+        // Dropping a previous task cancels it.
+        self.combining = Some(AsyncComputeTaskPool::get().spawn(async move {
+            let lexicons = sources
+                .iter()
+                .map(|d| d as &dyn Lexicon)
+                .collect::<Vec<_>>();
+            Dictionary(merge::combine(&lexicons, combine).into())
+        }));
+    }
+
+    /// Whether the game dictionary can be queried.
+    pub fn is_ready(&self) -> bool {
+        self.handle.is_some()
+    }
+
+    /// Whether sources are being combined, see [`WordsDictionary::combine_sources`].
+    pub fn is_combining(&self) -> bool {
+        self.combining.is_some()
+    }
+
+    /// The dictionary to query, `None` until the sources are first combined, see
+    /// [`WordsDictionary::combine_sources`].
+    pub fn get<'a>(
+        &self,
+        dictionaries: &'a Assets<Dictionary>,
+        overlay: &'a DictionaryOverlay,
+    ) -> Option<Layered<'a, Dictionary>> {
+        Some(Layered {
+            base: dictionaries.get(self.handle.as_ref()?)?,
+            overlay: &overlay.overlay,
+        })
+    }
+
+    /// Same as [`WordsDictionary::get`], owned to be moved into tasks, e.g. on the
+    /// `AsyncComputeTaskPool`. Later changes to the overlay are not seen by the snapshot.
+    pub fn snapshot(
+        &self,
+        dictionaries: &Assets<Dictionary>,
+        overlay: &DictionaryOverlay,
+    ) -> Option<SharedLayered> {
+        Some(SharedLayered {
+            base: dictionaries.get(self.handle.as_ref()?)?.0.clone(),
+            overlay: Arc::new(overlay.overlay.clone()),
+        })
+    }
+
+    /// Information about `word` from the first loaded sidecar which has some.
+    pub fn info<'a>(
+        &self,
+        word: &str,
+        metadata: &'a Assets<DictionaryMetadata>,
+    ) -> Option<&'a WordInfo> {
+        self.metadata
+            .iter()
+            .filter_map(|handle| metadata.get(handle))
+            .find_map(|m| m.0.get(word))
+    }
+}
+
+/// Allow and deny lists applied on top of every game dictionary, see [`WordOverlay`].
+#[derive(Resource)]
+pub struct DictionaryOverlay {
+    pub overlay: WordOverlay,
+    allow_path: PathBuf,
+    deny_path: PathBuf,
+}
+
+impl DictionaryOverlay {
+    /// Reads `allow.txt` and `deny.txt` from `dir`, a missing file is an empty list.
+    pub fn open(dir: &Path) -> Self {
+        let allow_path = dir.join("allow.txt");
+        let deny_path = dir.join("deny.txt");
+        let read = |path: &Path| match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    warn!("could not read {}: {e}", path.display());
+                }
+                vec![]
+            }
+        };
+        let (overlay, allow_report, deny_report) = WordOverlay::load_from(
+            read(&allow_path).as_slice(),
+            read(&deny_path).as_slice(),
+            &LoadOptions::default(),
+        );
+        for (path, report) in [(&allow_path, allow_report), (&deny_path, deny_report)] {
+            for rejected in &report.rejected {
+                warn!(
+                    "{}:{}: skipped \"{}\" ({:?})",
+                    path.display(),
+                    rejected.line_number,
+                    rejected.content,
+                    rejected.reason
+                );
+            }
+        }
+        Self {
+            overlay,
+            allow_path,
+            deny_path,
+        }
+    }
+
+    /// Allows `word` for this game and the next ones: removes it from the deny list file, and
+    /// appends it to the allow list file unless the base dictionary or the allow list already
+    /// has it.
+    pub fn accept_word(&mut self, word: &str, in_base: bool) -> std::io::Result<()> {
+        if self.overlay.undeny(word) {
+            remove_word(&self.deny_path, word)?;
+        }
+        if in_base || self.overlay.allow.contains(word) {
+            return Ok(());
+        }
+        self.overlay.allow(word);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.allow_path)?;
+        writeln!(file, "{word}")
+    }
+}
+
+/// Rewrites the word list at `path` without the lines which normalize to `word`, comments
+/// are kept.
+fn remove_word(path: &Path, word: &str) -> std::io::Result<()> {
+    let options = LoadOptions::default();
+    let kept = std::fs::read_to_string(path)?
+        .lines()
+        .filter(|line| options.normalize(line).ok().flatten().as_deref() != Some(word))
+        .map(|line| format!("{line}\n"))
+        .collect::<String>();
+    std::fs::write(path, kept)
+}
+
+/// A challenged word was accepted: it is allowed for this game and the next ones.
+#[derive(Event)]
+pub struct AcceptWord(pub String);
+
+pub(super) fn accept_words(
+    mut events: EventReader<AcceptWord>,
+    mut overlay: ResMut<DictionaryOverlay>,
+    words_dictionary: Option<Res<WordsDictionary>>,
+    dictionaries: Res<Assets<Dictionary>>,
+) {
+    for AcceptWord(word) in events.read() {
+        let Ok(Some(word)) = LoadOptions::default().normalize(word) else {
+            warn!("\"{word}\" can't be a word");
+            continue;
+        };
+        let dictionary = words_dictionary
+            .as_ref()
+            .and_then(|d| d.get(&dictionaries, &overlay));
+        if dictionary.as_ref().is_some_and(|d| d.contains(&word)) {
+            continue;
+        }
+        let in_base = dictionary.is_some_and(|d| d.base.contains(&word));
+        match overlay.accept_word(&word, in_base) {
+            Ok(()) => info!("\"{word}\" accepted"),
+            Err(e) => error!("could not save \"{word}\": {e}"),
+        }
+    }
+}
+
+/// Adds the dictionary combined by [`WordsDictionary::combine_sources`] once it is built.
+pub(super) fn finish_combining(
+    words_dictionary: Option<ResMut<WordsDictionary>>,
+    mut dictionaries: ResMut<Assets<Dictionary>>,
+) {
+    let Some(mut words_dictionary) = words_dictionary else {
+        return;
+    };
+    let is_finished = words_dictionary
+        .combining
+        .as_ref()
+        .is_some_and(|task| task.is_finished());
+    if !is_finished {
+        return;
+    }
+    let Some(task) = words_dictionary.combining.take() else {
+        return;
+    };
+    let combined = block_on(task);
+    match &words_dictionary.handle {
+        Some(handle) => dictionaries.insert(handle.id(), combined),
+        None => words_dictionary.handle = Some(dictionaries.add(combined)),
+    }
+}
+
+/// Logs dictionary (re)loads and combines hot-reloaded sources again.
+///
+/// Queries read the asset, so hot-reloaded words apply right away.
+pub(super) fn reload_dictionary(
+    mut events: EventReader<AssetEvent<Dictionary>>,
+    asset_server: Res<AssetServer>,
+    words_dictionary: Option<ResMut<WordsDictionary>>,
+    dictionaries: Res<Assets<Dictionary>>,
+) {
+    let mut modified = false;
+    for event in events.read() {
+        match event {
+            AssetEvent::LoadedWithDependencies { id } => {
+                info!("dictionary {:?} loaded", asset_server.get_path(*id));
+            }
+            AssetEvent::Modified { id } => {
+                if let Some(path) = asset_server.get_path(*id) {
+                    info!("dictionary {path} reloaded");
+                    modified = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(mut words_dictionary) = words_dictionary {
+        if modified && words_dictionary.handle.is_some() {
+            words_dictionary.combine_sources(&dictionaries);
+        }
+    }
+}
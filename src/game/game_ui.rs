@@ -1,7 +1,18 @@
-use bevy::{prelude::*, render::view::RenderLayers, ui::FocusPolicy};
+use bevy::{
+    prelude::*,
+    render::view::RenderLayers,
+    tasks::{block_on, AsyncComputeTaskPool, Task},
+    ui::FocusPolicy,
+};
 use bevy_mod_picking::picking_core::Pickable;
 
-use super::GameState;
+use crate::word_tree::{shared::SharedLayered, Lexicon};
+
+use super::{
+    dictionary::{AcceptWord, Dictionary, DictionaryMetadata, DictionaryOverlay, WordsDictionary},
+    setup::Table,
+    GameState, HoveredTile,
+};
 
 #[derive(Component)]
 pub struct GameMenuMarker;
@@ -9,6 +20,31 @@ pub struct GameMenuMarker;
 #[derive(Event)]
 pub struct ExitGame;
 
+/// Reports the dictionary loading progress, empty once playing.
+#[derive(Component)]
+pub struct LoadingText {
+    /// `Time::elapsed_seconds` when loading started.
+    started_at: f32,
+}
+
+/// Words under the pointer, with their definition when the dictionary has one, or the
+/// nearest valid words when they are not words.
+#[derive(Component, Default)]
+pub struct WordInfoText {
+    /// Next lines of text, looking for suggestions walks the dictionary so it runs on the
+    /// `AsyncComputeTaskPool`.
+    task: Option<Task<Vec<String>>>,
+    /// Dictionary given to the tasks, copied again only when the dictionary or the overlay
+    /// changes.
+    snapshot: Option<SharedLayered>,
+}
+
+/// Edits allowed between an invalid word and its "did you mean" suggestions.
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+const MAX_SUGGESTIONS: usize = 3;
+/// Accepts the invalid words of the hovered tile, see [`accept_hovered_words`].
+const ACCEPT_WORD_KEY: KeyCode = KeyCode::Return;
+
 pub fn game_unsetup_ui(mut commands: Commands, q_menus: Query<Entity, With<GameMenuMarker>>) {
     for e in q_menus.iter() {
         commands.entity(e).despawn_recursive();
@@ -59,7 +95,151 @@ pub fn button_system(
     }
 }
 
-pub fn game_setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+pub fn update_loading_text(
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    dictionary: Option<Res<WordsDictionary>>,
+    mut q_text: Query<(&mut Text, &LoadingText)>,
+) {
+    let Some(dictionary) = dictionary else {
+        return;
+    };
+    let (loaded, total) = dictionary.progress(&asset_server);
+    for (mut text, loading) in q_text.iter_mut() {
+        let elapsed = time.elapsed_seconds() - loading.started_at;
+        text.sections[0].value = format!(
+            "Loading {}... {loaded}/{total} files, {elapsed:.1}s",
+            dictionary.selection
+        );
+    }
+}
+
+pub fn show_loading_failed(mut q_text: Query<&mut Text, With<LoadingText>>) {
+    for mut text in q_text.iter_mut() {
+        text.sections[0].value = "Could not load the dictionary, see logs.".to_string();
+    }
+}
+
+pub fn hide_loading_text(mut q_text: Query<&mut Text, With<LoadingText>>) {
+    for mut text in q_text.iter_mut() {
+        text.sections[0].value.clear();
+    }
+}
+
+pub fn update_word_info(
+    hovered: Res<HoveredTile>,
+    q_table: Query<&Table>,
+    words_dictionary: Res<WordsDictionary>,
+    dictionaries: Res<Assets<Dictionary>>,
+    overlay: Res<DictionaryOverlay>,
+    metadata: Res<Assets<DictionaryMetadata>>,
+    mut q_text: Query<(&mut Text, &mut WordInfoText)>,
+) {
+    let Ok((mut text, mut info)) = q_text.get_single_mut() else {
+        return;
+    };
+    // Dropping the previous task cancels it.
+    info.task = None;
+    let base = words_dictionary
+        .get(&dictionaries, &overlay)
+        .map(|dictionary| &dictionary.base.0);
+    let is_stale = info
+        .snapshot
+        .as_ref()
+        .zip(base)
+        .is_none_or(|(snapshot, base)| !snapshot.base.ptr_eq(base));
+    if is_stale || overlay.is_changed() {
+        info.snapshot = words_dictionary.snapshot(&dictionaries, &overlay);
+    }
+    let (Some(position), Ok(table), Some(dictionary)) =
+        (hovered.0, q_table.get_single(), info.snapshot.clone())
+    else {
+        text.sections[0].value.clear();
+        return;
+    };
+    // Valid words are described right away, invalid ones are left to the task.
+    let lines = table
+        .0
+        .indexed_words_at(position)
+        .into_iter()
+        .map(|word| word.word.clone())
+        .map(|word| {
+            if !dictionary.contains(&word) {
+                return Err(word);
+            }
+            Ok(
+                match words_dictionary
+                    .info(&word, &metadata)
+                    .map(|i| i.to_string())
+                {
+                    Some(info) if !info.is_empty() => format!("{word}: {info}"),
+                    _ => word,
+                },
+            )
+        })
+        .collect::<Vec<Result<String, String>>>();
+    info.task = Some(AsyncComputeTaskPool::get().spawn(async move {
+        lines
+            .into_iter()
+            .map(|line| line.unwrap_or_else(|word| did_you_mean(&dictionary, word)))
+            .collect()
+    }));
+}
+
+fn did_you_mean(dictionary: &SharedLayered, word: String) -> String {
+    let suggestions = dictionary
+        .suggestions(&word, MAX_SUGGESTION_DISTANCE)
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|s| s.word)
+        .collect::<Vec<_>>();
+    let accept = format!("({ACCEPT_WORD_KEY:?} to accept)");
+    if suggestions.is_empty() {
+        return format!("{word}? {accept}");
+    }
+    format!("{word}? Did you mean {}? {accept}", suggestions.join(", "))
+}
+
+pub fn show_word_info(mut q_text: Query<(&mut Text, &mut WordInfoText)>) {
+    for (mut text, mut info) in q_text.iter_mut() {
+        if !info.task.as_ref().is_some_and(|task| task.is_finished()) {
+            continue;
+        }
+        if let Some(task) = info.task.take() {
+            text.sections[0].value = block_on(task).join("\n");
+        }
+    }
+}
+
+/// Sends an [`AcceptWord`] for each invalid word of the hovered tile when
+/// [`ACCEPT_WORD_KEY`] is pressed, when players agree a challenged word is valid.
+pub fn accept_hovered_words(
+    keys: Res<Input<KeyCode>>,
+    hovered: Res<HoveredTile>,
+    q_table: Query<&Table>,
+    words_dictionary: Res<WordsDictionary>,
+    dictionaries: Res<Assets<Dictionary>>,
+    overlay: Res<DictionaryOverlay>,
+    mut accept_word: EventWriter<AcceptWord>,
+) {
+    if !keys.just_pressed(ACCEPT_WORD_KEY) {
+        return;
+    }
+    let (Some(position), Ok(table), Some(dictionary)) = (
+        hovered.0,
+        q_table.get_single(),
+        words_dictionary.get(&dictionaries, &overlay),
+    ) else {
+        return;
+    };
+    for word in table.0.indexed_words_at(position) {
+        if !dictionary.contains(&word.word) {
+            accept_word.send(AcceptWord(word.word.clone()));
+        }
+    }
+}
+
+pub fn game_setup_ui(mut commands: Commands, asset_server: Res<AssetServer>, time: Res<Time>) {
     // ui camera
     commands.spawn((
         Camera2dBundle {
@@ -123,5 +303,38 @@ pub fn game_setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                         RenderLayers::layer(4),
                     ));
                 });
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: default(),
+                        font_size: 40.0,
+                        color: Color::rgb(0.9, 0.9, 0.9),
+                    },
+                ),
+                LoadingText {
+                    started_at: time.elapsed_seconds(),
+                },
+                RenderLayers::layer(4),
+                Pickable::IGNORE,
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: default(),
+                        font_size: 24.0,
+                        color: Color::rgb(0.9, 0.9, 0.9),
+                    },
+                )
+                .with_style(Style {
+                    max_width: Val::Px(600.0),
+                    margin: UiRect::left(Val::Px(20.0)),
+                    ..default()
+                }),
+                WordInfoText::default(),
+                RenderLayers::layer(4),
+                Pickable::IGNORE,
+            ));
         });
 }
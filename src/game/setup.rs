@@ -7,13 +7,14 @@ use bevy_mod_picking::{
     PickableBundle,
 };
 use bevy_pancam::*;
-use std::{collections::HashMap, fs::File, io::BufReader};
+use std::collections::HashMap;
 
-use crate::{word_table::Tile, word_tree::load_from};
+use crate::word_table::Tile;
 
-use super::WordsDictionary;
+use super::board::{BoardLayoutHandle, BOARD_LAYOUT};
+use super::dictionary::{DictionaryRegistry, SelectedDictionaries, WordsDictionary};
 
-use super::{LAYER_DRAG, LAYER_INVENTORY};
+use super::{HoveredTile, LAYER_DRAG, LAYER_INVENTORY};
 
 #[derive(Component)]
 pub struct Table(pub crate::word_table::Table);
@@ -32,16 +33,26 @@ pub struct GameMarker;
 
 pub(super) fn unsetup(mut commands: Commands, q_to_despawn: Query<Entity, With<GameMarker>>) {
     commands.remove_resource::<WordsDictionary>();
+    commands.remove_resource::<BoardLayoutHandle>();
+    commands.remove_resource::<HoveredTile>();
     for e in q_to_despawn.iter() {
         commands.entity(e).despawn_recursive();
     }
 }
 
-pub(super) fn setup(mut commands: Commands) {
-    let f = File::open("assets/scrabble.en.txt").expect("Could not read file.");
-    let reader = BufReader::new(f);
-    let tree_root = load_from(reader);
-    commands.insert_resource(WordsDictionary(tree_root));
+pub(super) fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    selected: Res<SelectedDictionaries>,
+    registry: Res<DictionaryRegistry>,
+) {
+    commands.insert_resource(WordsDictionary::load(
+        selected.0.clone(),
+        &registry,
+        &asset_server,
+    ));
+    commands.insert_resource(BoardLayoutHandle(asset_server.load(BOARD_LAYOUT)));
+    commands.insert_resource(HoveredTile::default());
     // 2d world camera
     commands.spawn((
         Camera2dBundle::default(),
@@ -51,52 +62,51 @@ pub(super) fn setup(mut commands: Commands) {
         GameMarker,
     ));
 
-    let table = crate::word_table::Table {
-        tiles: HashMap::from([
-            (
-                (0, 0).into(),
-                Tile {
-                    team: 0,
-                    character: 'h',
-                },
-            ),
-            (
-                (0, 1).into(),
-                Tile {
-                    team: 0,
-                    character: 'e',
-                },
-            ),
-            (
-                (0, 2).into(),
-                Tile {
-                    team: 0,
-                    character: 'y',
-                },
-            ),
-            (
-                (1, 2).into(),
-                Tile {
-                    team: 0,
-                    character: 'o',
-                },
-            ),
-            (
-                (2, 2).into(),
-                Tile {
-                    team: 0,
-                    character: 'u',
-                },
-            ),
-            (
-                (4, 2).into(),
-                Tile {
-                    team: 0,
-                    character: 'a',
-                },
-            ),
-        ]),
-    };
+    let tiles = HashMap::from([
+        (
+            (0, 0).into(),
+            Tile {
+                team: 0,
+                character: 'h',
+            },
+        ),
+        (
+            (0, 1).into(),
+            Tile {
+                team: 0,
+                character: 'e',
+            },
+        ),
+        (
+            (0, 2).into(),
+            Tile {
+                team: 0,
+                character: 'y',
+            },
+        ),
+        (
+            (1, 2).into(),
+            Tile {
+                team: 0,
+                character: 'o',
+            },
+        ),
+        (
+            (2, 2).into(),
+            Tile {
+                team: 0,
+                character: 'u',
+            },
+        ),
+        (
+            (4, 2).into(),
+            Tile {
+                team: 0,
+                character: 'a',
+            },
+        ),
+    ]);
+    let table = crate::word_table::Table::new(tiles).expect("starting tiles are on the table");
     commands.spawn((Table(table), GameMarker));
 }
 
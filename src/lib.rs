@@ -0,0 +1,2 @@
+pub mod word_table;
+pub mod word_tree;
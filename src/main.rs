@@ -1,6 +1,7 @@
+mod cli;
 mod game;
-pub mod word_table;
-pub mod word_tree;
+
+use rswords::{word_table, word_tree};
 
 use bevy::prelude::*;
 use game::GamePlugin;
@@ -24,5 +25,13 @@ fn main() {
         .with(filter_layer)
         .init();
     */
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    if let Some(result) = cli::run(&args) {
+        if let Err(e) = result {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        return;
+    }
     App::new().add_plugins(GamePlugin).run();
 }
@@ -1,70 +1,416 @@
 use glam::IVec2;
-use std::{collections::HashMap, vec};
+use std::collections::{HashMap, HashSet};
 
-#[derive(Clone)]
+use crate::word_tree::Lexicon;
+
+pub mod premium;
+pub mod shape;
+
+use premium::Premium;
+use shape::{BoardShape, Bounds};
+
+#[derive(Clone, Debug)]
 pub struct Tile {
     pub team: usize,
     pub character: char,
 }
 
+/// Tiles are placed within this distance of the origin, so walking along words never overflows.
+pub const MAX_COORDINATE: i32 = 1 << 24;
+
+/// Why a tile could not be placed, taken or moved, the table is left unchanged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableError {
+    /// A tile is already at this position.
+    Occupied(IVec2),
+    /// There is no tile at this position.
+    EmptySource(IVec2),
+    /// Tiles can't be placed at this position.
+    OutOfBounds(IVec2),
+}
+
+impl std::fmt::Display for TableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableError::Occupied(p) => write!(f, "there is already a tile at {p}"),
+            TableError::EmptySource(p) => write!(f, "there is no tile at {p}"),
+            TableError::OutOfBounds(p) => write!(f, "{p} is outside of the table"),
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
 pub struct Table {
-    pub tiles: HashMap<IVec2, Tile>,
+    tiles: HashMap<IVec2, Tile>,
+    /// Words of at least two letters, kept up to date by the methods moving tiles.
+    words: HashSet<IndexedWord>,
+    shape: BoardShape,
+    /// Premium squares no tile was placed on yet.
+    premiums: HashMap<IVec2, Premium>,
 }
 
 impl Table {
-    pub fn get_vertical_words(&self) -> Vec<WordOnTable> {
-        let mut ordered_keys = self.tiles.keys().copied().collect::<Vec<IVec2>>();
-        ordered_keys.reverse();
-        ordered_keys.sort_unstable_by_key(|v| -v.y);
-        let mut horizontal_words = vec![];
-        while let Some(mut moving_letter_pos) = ordered_keys.pop() {
-            let mut current_word = WordOnTable {
-                position: moving_letter_pos,
-                tiles: vec![],
-            };
-            while let Some(next_letter) = self.tiles.get(&moving_letter_pos) {
-                current_word.tiles.push(next_letter);
-                moving_letter_pos += IVec2::Y;
+    /// A table where tiles can be placed anywhere within [`MAX_COORDINATE`], fails if one of
+    /// `tiles` is not.
+    pub fn new(tiles: HashMap<IVec2, Tile>) -> Result<Self, TableError> {
+        Table::with_shape(tiles, BoardShape::Unbounded)
+    }
+
+    /// A table where tiles can only be placed inside `shape`, fails if one of `tiles` is not.
+    pub fn with_shape(tiles: HashMap<IVec2, Tile>, shape: BoardShape) -> Result<Self, TableError> {
+        let mut table = Table {
+            tiles,
+            words: HashSet::new(),
+            shape,
+            premiums: HashMap::new(),
+        };
+        // Before reading words, which walks past the tiles.
+        if let Some(position) = table
+            .tiles
+            .keys()
+            .find(|p| table.check_bounds(**p).is_err())
+        {
+            return Err(TableError::OutOfBounds(*position));
+        }
+        table.words = table
+            .get_words()
+            .without_single_letters()
+            .iter()
+            .map(IndexedWord::from)
+            .collect();
+        Ok(table)
+    }
+
+    pub fn tiles(&self) -> &HashMap<IVec2, Tile> {
+        &self.tiles
+    }
+
+    pub fn shape(&self) -> &BoardShape {
+        &self.shape
+    }
+
+    /// Replaces the premium squares, those under a tile are used right away.
+    pub fn set_premiums(&mut self, premiums: HashMap<IVec2, Premium>) {
+        self.premiums = premiums;
+        self.premiums
+            .retain(|position, _| !self.tiles.contains_key(position));
+    }
+
+    /// Premium squares no tile was placed on yet.
+    pub fn premiums(&self) -> &HashMap<IVec2, Premium> {
+        &self.premiums
+    }
+
+    /// Whether a tile could be placed at `position`, if it was free.
+    pub fn is_on_board(&self, position: IVec2) -> bool {
+        self.check_bounds(position).is_ok()
+    }
+
+    /// Smallest rectangle containing every tile, `None` if the table is empty.
+    pub fn bounding_box(&self) -> Option<Bounds> {
+        Bounds::around(self.tiles.keys().copied())
+    }
+
+    /// Tiles inside `bounds`, in no particular order.
+    pub fn tiles_in(&self, bounds: Bounds) -> impl Iterator<Item = (IVec2, &Tile)> {
+        self.tiles
+            .iter()
+            .filter(move |(position, _)| bounds.contains(**position))
+            .map(|(position, tile)| (*position, tile))
+    }
+
+    /// Words of at least two letters, in no particular order, without scanning the table.
+    pub fn indexed_words(&self) -> impl Iterator<Item = &IndexedWord> {
+        self.words.iter()
+    }
+
+    /// Puts `tile` at the free `position`.
+    pub fn place_tile(&mut self, position: IVec2, tile: Tile) -> Result<WordChanges, TableError> {
+        self.check_bounds(position)?;
+        if self.tiles.contains_key(&position) {
+            return Err(TableError::Occupied(position));
+        }
+        let mut changes = self.update(&[position], |tiles| {
+            tiles.insert(position, tile);
+        });
+        changes.premiums = self.use_premiums(&[position]);
+        Ok(changes)
+    }
+
+    /// Takes the tile at `position` off the table.
+    pub fn take_tile(&mut self, position: IVec2) -> Result<(Tile, WordChanges), TableError> {
+        if !self.tiles.contains_key(&position) {
+            return Err(TableError::EmptySource(position));
+        }
+        let mut taken = None;
+        let changes = self.update(&[position], |tiles| taken = tiles.remove(&position));
+        let tile = taken.ok_or(TableError::EmptySource(position))?;
+        Ok((tile, changes))
+    }
+
+    /// Moves the tile at `from` to the free position `to`.
+    pub fn move_tile(&mut self, from: IVec2, to: IVec2) -> Result<WordChanges, TableError> {
+        self.check_bounds(to)?;
+        if !self.tiles.contains_key(&from) {
+            return Err(TableError::EmptySource(from));
+        }
+        if self.tiles.contains_key(&to) {
+            return Err(TableError::Occupied(to));
+        }
+        let mut changes = self.update(&[from, to], |tiles| {
+            if let Some(tile) = tiles.remove(&from) {
+                tiles.insert(to, tile);
+            }
+        });
+        changes.premiums = self.use_premiums(&[to]);
+        Ok(changes)
+    }
+
+    /// Exchanges the tiles at `a` and `b`.
+    pub fn swap_tiles(&mut self, a: IVec2, b: IVec2) -> Result<WordChanges, TableError> {
+        for position in [a, b] {
+            if !self.tiles.contains_key(&position) {
+                return Err(TableError::EmptySource(position));
+            }
+        }
+        if a == b {
+            return Ok(WordChanges::default());
+        }
+        Ok(self.update(&[a, b], |tiles| {
+            if let (Some(tile_a), Some(tile_b)) = (tiles.remove(&a), tiles.remove(&b)) {
+                tiles.insert(a, tile_b);
+                tiles.insert(b, tile_a);
             }
-            let word_range = current_word.position.y
-                ..=(current_word.position.y + current_word.tiles.len() as i32);
-            ordered_keys.retain(|e| e.x != current_word.position.x || !word_range.contains(&e.y));
-            horizontal_words.push(current_word);
-        }
-        horizontal_words
-    }
-    pub fn get_horizontal_words(&self) -> Vec<WordOnTable> {
-        let mut ordered_keys = self.tiles.keys().copied().collect::<Vec<IVec2>>();
-        ordered_keys.reverse();
-        ordered_keys.sort_unstable_by_key(|v| -v.x);
-        let mut horizontal_words = vec![];
-        while let Some(mut moving_letter_pos) = ordered_keys.pop() {
-            let mut current_word = WordOnTable {
-                position: moving_letter_pos,
-                tiles: vec![],
-            };
-            while let Some(next_letter) = self.tiles.get(&moving_letter_pos) {
-                current_word.tiles.push(next_letter);
-                moving_letter_pos += IVec2::X;
+        }))
+    }
+
+    /// Removes the premium squares at `positions`, now covered by a tile.
+    fn use_premiums(&mut self, positions: &[IVec2]) -> Vec<(IVec2, Premium)> {
+        positions
+            .iter()
+            .filter_map(|p| self.premiums.remove(p).map(|premium| (*p, premium)))
+            .collect()
+    }
+
+    fn check_bounds(&self, position: IVec2) -> Result<(), TableError> {
+        if position.abs().max_element() > MAX_COORDINATE || !self.shape.contains(position) {
+            return Err(TableError::OutOfBounds(position));
+        }
+        Ok(())
+    }
+
+    /// Applies `change` to the tiles, which may only touch `positions`, and updates the words
+    /// around them.
+    fn update<F>(&mut self, positions: &[IVec2], change: F) -> WordChanges
+    where
+        F: FnOnce(&mut HashMap<IVec2, Tile>),
+    {
+        let before = self.words_near(positions);
+        change(&mut self.tiles);
+        let after = self.words_near(positions);
+        let destroyed = before.difference(&after).cloned().collect::<Vec<_>>();
+        let created = after.difference(&before).cloned().collect::<Vec<_>>();
+        for word in &destroyed {
+            self.words.remove(word);
+        }
+        self.words.extend(created.iter().cloned());
+        WordChanges::new(destroyed, created)
+    }
+
+    /// Words of at least two letters with a tile at or next to one of `positions`: the only
+    /// words which can change when these positions do.
+    fn words_near(&self, positions: &[IVec2]) -> HashSet<IndexedWord> {
+        let mut words = HashSet::new();
+        for position in positions {
+            for offset in [IVec2::ZERO].iter().chain(&SIDES) {
+                for direction in Direction::ALL {
+                    if let Some(word) = self.run_through(*position + *offset, direction) {
+                        if word.tiles.len() > 1 {
+                            words.insert(IndexedWord::from(&word));
+                        }
+                    }
+                }
             }
-            let word_range = current_word.position.x
-                ..=(current_word.position.x + current_word.tiles.len() as i32);
-            ordered_keys.retain(|e| e.y != current_word.position.y || !word_range.contains(&e.x));
-            horizontal_words.push(current_word);
         }
-        horizontal_words
+        words
+    }
+
+    /// The run going in `direction` which the tile at `position` is part of.
+    fn run_through(&self, position: IVec2, direction: Direction) -> Option<WordOnTable<'_>> {
+        let step = direction.step();
+        self.tiles.get(&position)?;
+        let mut start = position;
+        while self.tiles.contains_key(&(start - step)) {
+            start -= step;
+        }
+        let mut word = WordOnTable {
+            position: start,
+            direction,
+            tiles: vec![],
+        };
+        let mut position = start;
+        while let Some(tile) = self.tiles.get(&position) {
+            word.tiles.push(tile);
+            position += step;
+        }
+        Some(word)
+    }
+
+    /// Runs of contiguous tiles going in `direction`, a lone tile is a run of one tile.
+    ///
+    /// Runs are sorted by line, then by position in the line. Each run starts at a tile with
+    /// no tile before it, so every tile is visited once per direction.
+    pub fn get_runs(&self, direction: Direction) -> Vec<WordOnTable<'_>> {
+        let step = direction.step();
+        let mut starts = self
+            .tiles
+            .keys()
+            .copied()
+            .filter(|position| !self.tiles.contains_key(&(*position - step)))
+            .collect::<Vec<IVec2>>();
+        starts.sort_unstable_by_key(|start| (start.dot(IVec2::ONE - step), start.dot(step)));
+        starts
+            .into_iter()
+            .map(|start| {
+                let mut word = WordOnTable {
+                    position: start,
+                    direction,
+                    tiles: vec![],
+                };
+                let mut position = start;
+                while let Some(tile) = self.tiles.get(&position) {
+                    word.tiles.push(tile);
+                    position += step;
+                }
+                word
+            })
+            .collect()
+    }
+
+    pub fn get_vertical_words(&self) -> Vec<WordOnTable<'_>> {
+        self.get_runs(Direction::Vertical)
+    }
+
+    pub fn get_horizontal_words(&self) -> Vec<WordOnTable<'_>> {
+        self.get_runs(Direction::Horizontal)
     }
 
-    pub fn get_words(&self) -> TableWordsList {
+    /// All runs of tiles, see [`TableWordsList::without_single_letters`] to only keep words.
+    pub fn get_words(&self) -> TableWordsList<'_> {
         TableWordsList {
             horizontal: self.get_horizontal_words(),
             vertical: self.get_vertical_words(),
         }
     }
+
+    /// Checks every word of at least two letters against `lexicon`, from the index.
+    ///
+    /// Words are sorted like [`Table::get_words`]: horizontal words first, by line then by
+    /// position in the line.
+    pub fn validate<L: Lexicon + ?Sized>(&self, lexicon: &L) -> Validation<'_> {
+        let mut validation = Validation {
+            valid: vec![],
+            invalid: vec![],
+            invalid_tiles: HashSet::new(),
+            components: self.connected_components(),
+        };
+        let mut words = self.indexed_words().collect::<Vec<_>>();
+        words.sort_unstable_by_key(|word| {
+            let step = word.direction.step();
+            let line = word.position.dot(IVec2::ONE - step);
+            (word.direction as u8, line, word.position.dot(step))
+        });
+        for word in words {
+            if lexicon.contains(&word.word) {
+                validation.valid.push(word);
+            } else {
+                validation.invalid_tiles.extend(word.positions());
+                validation.invalid.push(word);
+            }
+        }
+        validation
+    }
+
+    /// Groups of tiles touching each other by a side, largest first.
+    ///
+    /// Positions are sorted by row then column, groups of the same size by their first position.
+    pub fn connected_components(&self) -> Vec<Vec<IVec2>> {
+        let mut visited = HashSet::with_capacity(self.tiles.len());
+        let mut components = vec![];
+        for start in self.tiles.keys() {
+            if !visited.insert(*start) {
+                continue;
+            }
+            let mut component = vec![];
+            let mut stack = vec![*start];
+            while let Some(position) = stack.pop() {
+                component.push(position);
+                for side in SIDES {
+                    let neighbour = position + side;
+                    if self.tiles.contains_key(&neighbour) && visited.insert(neighbour) {
+                        stack.push(neighbour);
+                    }
+                }
+            }
+            component.sort_unstable_by_key(|p| (p.y, p.x));
+            components.push(component);
+        }
+        components.sort_unstable_by_key(|c| (std::cmp::Reverse(c.len()), c[0].y, c[0].x));
+        components
+    }
+
+    /// Returns true if all tiles are connected, which an empty table is.
+    pub fn is_connected(&self) -> bool {
+        self.connected_components().len() <= 1
+    }
+
+    /// The horizontal then vertical word which the tile at `position` is part of.
+    pub fn words_at(&self, position: IVec2) -> Vec<WordOnTable<'_>> {
+        Direction::ALL
+            .into_iter()
+            .filter_map(|direction| self.run_through(position, direction))
+            .collect()
+    }
+
+    /// Words of at least two letters through the tile at `position`, horizontal first, from
+    /// the index.
+    pub fn indexed_words_at(&self, position: IVec2) -> Vec<&IndexedWord> {
+        Direction::ALL
+            .into_iter()
+            .filter_map(|direction| self.run_through(position, direction))
+            .filter(|run| run.tiles.len() > 1)
+            .filter_map(|run| self.words.get(&IndexedWord::from(&run)))
+            .collect()
+    }
+}
+
+/// Offsets to the tiles touching a tile by a side.
+const SIDES: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+
+/// Reading direction of a word: left to right, or with increasing `y`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 2] = [Direction::Horizontal, Direction::Vertical];
+
+    /// Offset from a tile to the next one.
+    pub fn step(self) -> IVec2 {
+        match self {
+            Direction::Horizontal => IVec2::X,
+            Direction::Vertical => IVec2::Y,
+        }
+    }
 }
 
 pub struct WordOnTable<'a> {
+    /// Position of the first tile.
     pub position: IVec2,
+    pub direction: Direction,
     pub tiles: Vec<&'a Tile>,
 }
 
@@ -76,6 +422,114 @@ impl<'a> WordOnTable<'a> {
             .map(|tile| tile.character)
             .collect::<String>();
     }
+
+    pub fn start(&self) -> IVec2 {
+        self.position
+    }
+
+    /// Position of the last tile.
+    pub fn end(&self) -> IVec2 {
+        self.position + self.direction.step() * (self.tiles.len() as i32 - 1).max(0)
+    }
+
+    /// Positions of the tiles, in reading order.
+    pub fn positions(&self) -> impl Iterator<Item = IVec2> + '_ {
+        (0..self.tiles.len() as i32).map(|i| self.position + self.direction.step() * i)
+    }
+
+    /// Returns true if one of the tiles is at `position`.
+    pub fn contains(&self, position: IVec2) -> bool {
+        run_contains(self.position, self.direction, self.tiles.len(), position)
+    }
+}
+
+fn run_contains(start: IVec2, direction: Direction, len: usize, position: IVec2) -> bool {
+    let offset = position - start;
+    let index = offset.dot(direction.step());
+    offset == direction.step() * index && (0..len as i32).contains(&index)
+}
+
+/// A word of a [`Table`], owned so that it outlives the tiles it was read from.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IndexedWord {
+    /// Position of the first tile.
+    pub position: IVec2,
+    pub direction: Direction,
+    pub word: String,
+}
+
+impl IndexedWord {
+    /// Returns true if one of the tiles is at `position`.
+    pub fn contains(&self, position: IVec2) -> bool {
+        run_contains(
+            self.position,
+            self.direction,
+            self.word.chars().count(),
+            position,
+        )
+    }
+
+    /// Positions of the tiles, from the first one.
+    pub fn positions(&self) -> impl Iterator<Item = IVec2> + '_ {
+        let step = self.direction.step();
+        (0..self.word.chars().count() as i32).map(move |i| self.position + step * i)
+    }
+
+    /// Returns true if both words have a tile in common and go in the same direction.
+    fn overlaps(&self, other: &IndexedWord) -> bool {
+        self.direction == other.direction && self.positions().any(|p| other.contains(p))
+    }
+}
+
+impl From<&WordOnTable<'_>> for IndexedWord {
+    fn from(value: &WordOnTable<'_>) -> Self {
+        IndexedWord {
+            position: value.position,
+            direction: value.direction,
+            word: value.get_word(),
+        }
+    }
+}
+
+/// Words of a [`Table`] affected by a change, each list sorted by direction then position.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WordChanges {
+    /// Words sharing no tile with a previous word.
+    pub created: Vec<IndexedWord>,
+    /// Words sharing no tile with a new word.
+    pub destroyed: Vec<IndexedWord>,
+    /// Previous words and the new word sharing one of their tiles, when a word was extended,
+    /// shortened, or had a letter replaced.
+    pub changed: Vec<(IndexedWord, IndexedWord)>,
+    /// Premium squares covered for the first time, which no longer apply to later changes.
+    pub premiums: Vec<(IVec2, Premium)>,
+}
+
+impl WordChanges {
+    /// Pairs each new word with the first overlapping previous word: when words are merged
+    /// or split, the extra words are created or destroyed.
+    fn new(mut destroyed: Vec<IndexedWord>, mut created: Vec<IndexedWord>) -> Self {
+        let order = |w: &IndexedWord| (w.direction as u8, w.position.y, w.position.x);
+        destroyed.sort_unstable_by_key(order);
+        created.sort_unstable_by_key(order);
+        let mut changes = WordChanges::default();
+        for word in created {
+            match destroyed.iter().position(|old| old.overlaps(&word)) {
+                Some(i) => changes.changed.push((destroyed.remove(i), word)),
+                None => changes.created.push(word),
+            }
+        }
+        changes.destroyed = destroyed;
+        changes
+    }
+
+    /// Whether no word changed and no premium square was used.
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty()
+            && self.destroyed.is_empty()
+            && self.changed.is_empty()
+            && self.premiums.is_empty()
+    }
 }
 
 pub struct TableWordsList<'a> {
@@ -83,61 +537,108 @@ pub struct TableWordsList<'a> {
     pub vertical: Vec<WordOnTable<'a>>,
 }
 
+impl<'a> TableWordsList<'a> {
+    /// Drops the runs of a single tile, which are only words in the other direction.
+    pub fn without_single_letters(mut self) -> Self {
+        self.horizontal.retain(|w| w.tiles.len() > 1);
+        self.vertical.retain(|w| w.tiles.len() > 1);
+        self
+    }
+
+    /// Horizontal then vertical words.
+    pub fn iter(&self) -> impl Iterator<Item = &WordOnTable<'a>> {
+        self.horizontal.iter().chain(self.vertical.iter())
+    }
+}
+
+/// Words of a [`Table`] checked against a dictionary, see [`Table::validate`].
+pub struct Validation<'a> {
+    pub valid: Vec<&'a IndexedWord>,
+    pub invalid: Vec<&'a IndexedWord>,
+    /// Positions of the tiles which are part of at least one invalid word.
+    pub invalid_tiles: HashSet<IVec2>,
+    /// See [`Table::connected_components`].
+    pub components: Vec<Vec<IVec2>>,
+}
+
+impl<'a> Validation<'a> {
+    /// Returns true if every word of the table is valid.
+    pub fn is_valid(&self) -> bool {
+        self.invalid.is_empty()
+    }
+
+    /// Groups of tiles cut off from the largest one.
+    pub fn islands(&self) -> &[Vec<IVec2>] {
+        self.components.get(1..).unwrap_or_default()
+    }
+
+    /// Returns true if the table has tiles, all connected and all part of valid words.
+    pub fn is_complete(&self) -> bool {
+        self.components.len() == 1 && self.is_valid()
+    }
+}
+
+#[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
 
     use glam::IVec2;
+    use proptest::prelude::*;
 
-    use super::{Table, Tile};
+    use super::{
+        premium::Premium,
+        shape::{BoardShape, Bounds},
+        Direction, IndexedWord, Table, TableError, Tile, MAX_COORDINATE,
+    };
+    use crate::word_tree::load_from;
 
     #[test]
     fn table_get_words() {
-        let table = Table {
-            tiles: HashMap::from([
-                (
-                    (0, 0).into(),
-                    Tile {
-                        team: 0,
-                        character: 'h',
-                    },
-                ),
-                (
-                    (0, 1).into(),
-                    Tile {
-                        team: 0,
-                        character: 'e',
-                    },
-                ),
-                (
-                    (0, 2).into(),
-                    Tile {
-                        team: 0,
-                        character: 'y',
-                    },
-                ),
-                (
-                    (1, 2).into(),
-                    Tile {
-                        team: 0,
-                        character: 'o',
-                    },
-                ),
-                (
-                    (2, 2).into(),
-                    Tile {
-                        team: 0,
-                        character: 'u',
-                    },
-                ),
-                (
-                    (4, 2).into(),
-                    Tile {
-                        team: 0,
-                        character: 'a',
-                    },
-                ),
-            ]),
-        };
+        let table = Table::new(HashMap::from([
+            (
+                (0, 0).into(),
+                Tile {
+                    team: 0,
+                    character: 'h',
+                },
+            ),
+            (
+                (0, 1).into(),
+                Tile {
+                    team: 0,
+                    character: 'e',
+                },
+            ),
+            (
+                (0, 2).into(),
+                Tile {
+                    team: 0,
+                    character: 'y',
+                },
+            ),
+            (
+                (1, 2).into(),
+                Tile {
+                    team: 0,
+                    character: 'o',
+                },
+            ),
+            (
+                (2, 2).into(),
+                Tile {
+                    team: 0,
+                    character: 'u',
+                },
+            ),
+            (
+                (4, 2).into(),
+                Tile {
+                    team: 0,
+                    character: 'a',
+                },
+            ),
+        ]))
+        .unwrap();
         let words = table.get_words();
         assert!(words.horizontal.iter().any(|t| t.get_word() == "e"));
         assert!(words
@@ -156,5 +657,454 @@ mod tests {
         assert!(words.vertical.iter().any(|t| t.get_word() == "a"));
         assert!(words.vertical.iter().any(|t| t.get_word() == "o"));
         assert_eq!(words.vertical.len(), 4);
+
+        let words_at = |x, y| {
+            table
+                .words_at(IVec2::new(x, y))
+                .iter()
+                .map(|w| w.get_word())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(words_at(0, 2), vec!["you", "hey"]);
+        assert_eq!(words_at(2, 2), vec!["you", "u"]);
+        assert!(words_at(3, 2).is_empty());
+
+        let words = table.get_words().without_single_letters();
+        let words = words
+            .iter()
+            .map(|w| (w.get_word(), w.direction, w.start(), w.end()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            words,
+            vec![
+                (
+                    "you".to_string(),
+                    Direction::Horizontal,
+                    IVec2::new(0, 2),
+                    IVec2::new(2, 2)
+                ),
+                (
+                    "hey".to_string(),
+                    Direction::Vertical,
+                    IVec2::new(0, 0),
+                    IVec2::new(0, 2)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn word_positions() {
+        let table = Table::new(HashMap::from([
+            (
+                (3, -1).into(),
+                Tile {
+                    team: 0,
+                    character: 'o',
+                },
+            ),
+            (
+                (3, 0).into(),
+                Tile {
+                    team: 0,
+                    character: 'n',
+                },
+            ),
+        ]))
+        .unwrap();
+        let words = table.get_words();
+        assert_eq!(words.horizontal.len(), 2);
+        let [word] = words.vertical.as_slice() else {
+            panic!("expected a single vertical word");
+        };
+        assert_eq!(word.get_word(), "on");
+        assert_eq!(
+            word.positions().collect::<Vec<_>>(),
+            vec![IVec2::new(3, -1), IVec2::new(3, 0)]
+        );
+        assert!(word.contains(IVec2::new(3, 0)));
+        assert!(!word.contains(IVec2::new(3, 1)));
+        assert!(!word.contains(IVec2::new(2, 0)));
+        assert!(words.without_single_letters().horizontal.is_empty());
+        let [indexed] = table.indexed_words_at(IVec2::new(3, 0))[..] else {
+            panic!("expected a single indexed word");
+        };
+        assert_eq!(indexed.word, "on");
+        assert!(table.indexed_words_at(IVec2::new(3, 1)).is_empty());
+    }
+
+    #[test]
+    fn validate() {
+        let tile = |character| Tile { team: 0, character };
+        let mut table = Table::new(HashMap::from([
+            ((0, 0).into(), tile('h')),
+            ((0, 1).into(), tile('e')),
+            ((0, 2).into(), tile('y')),
+            ((1, 2).into(), tile('o')),
+            ((2, 2).into(), tile('u')),
+            ((4, 2).into(), tile('a')),
+        ]))
+        .unwrap();
+        let dictionary = load_from("hey\nyou\n".as_bytes());
+        let validation = table.validate(&dictionary);
+        assert!(validation.is_valid());
+        assert_eq!(validation.valid.len(), 2);
+        assert!(validation.invalid_tiles.is_empty());
+
+        table.place_tile((3, 2).into(), tile('r')).unwrap();
+        table.place_tile((3, 3).into(), tile('x')).unwrap();
+        let validation = table.validate(&dictionary);
+        assert!(!validation.is_valid());
+        let invalid = validation
+            .invalid
+            .iter()
+            .map(|w| w.word.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(invalid, vec!["youra", "rx"]);
+        assert_eq!(validation.valid[0].word, "hey");
+        assert_eq!(validation.invalid_tiles.len(), 6);
+        assert!(validation.invalid_tiles.contains(&IVec2::new(3, 3)));
+        assert!(!validation.invalid_tiles.contains(&IVec2::new(0, 0)));
+    }
+
+    #[test]
+    fn connectivity() {
+        let tile = |character| Tile { team: 0, character };
+        let mut table = Table::new(HashMap::new()).unwrap();
+        assert!(table.is_connected());
+        let dictionary = load_from("hey\nyou\nhi\n".as_bytes());
+        assert!(!table.validate(&dictionary).is_complete());
+
+        for (position, character) in [((0, 0), 'h'), ((0, 1), 'e'), ((0, 2), 'y')] {
+            table.place_tile(position.into(), tile(character)).unwrap();
+        }
+        table.place_tile((1, 2).into(), tile('o')).unwrap();
+        table.place_tile((2, 2).into(), tile('u')).unwrap();
+        table.place_tile((5, 5).into(), tile('h')).unwrap();
+        table.place_tile((6, 5).into(), tile('i')).unwrap();
+        table.place_tile((4, 0).into(), tile('a')).unwrap();
+        assert!(!table.is_connected());
+        assert_eq!(
+            table.connected_components(),
+            vec![
+                vec![
+                    IVec2::new(0, 0),
+                    IVec2::new(0, 1),
+                    IVec2::new(0, 2),
+                    IVec2::new(1, 2),
+                    IVec2::new(2, 2)
+                ],
+                vec![IVec2::new(5, 5), IVec2::new(6, 5)],
+                vec![IVec2::new(4, 0)],
+            ]
+        );
+        let validation = table.validate(&dictionary);
+        assert!(validation.is_valid());
+        assert_eq!(validation.islands().len(), 2);
+        assert!(!validation.is_complete());
+
+        table.take_tile(IVec2::new(4, 0)).unwrap();
+        table.take_tile(IVec2::new(5, 5)).unwrap();
+        table.take_tile(IVec2::new(6, 5)).unwrap();
+        let validation = table.validate(&dictionary);
+        assert!(validation.islands().is_empty());
+        assert!(validation.is_complete());
+
+        // Diagonals don't connect tiles.
+        table.place_tile((3, 3).into(), tile('a')).unwrap();
+        assert_eq!(table.connected_components().len(), 2);
+    }
+
+    /// Runs found by scanning every line of the bounding box of the tiles.
+    fn runs_brute_force(table: &Table, direction: Direction) -> Vec<(IVec2, String)> {
+        let step = direction.step();
+        let across = IVec2::ONE - step;
+        let (min, max) = table.tiles().keys().fold(
+            (IVec2::splat(i32::MAX), IVec2::splat(i32::MIN)),
+            |(min, max), p| (min.min(*p), max.max(*p)),
+        );
+        let mut runs = vec![];
+        for line in min.dot(across)..=max.dot(across) {
+            let mut run: Option<(IVec2, String)> = None;
+            for along in min.dot(step)..=max.dot(step) + 1 {
+                let position = across * line + step * along;
+                match (table.tiles().get(&position), &mut run) {
+                    (Some(tile), Some((_, word))) => word.push(tile.character),
+                    (Some(tile), None) => run = Some((position, tile.character.to_string())),
+                    (None, run) => runs.extend(run.take()),
+                }
+            }
+        }
+        runs
+    }
+
+    proptest! {
+        #[test]
+        fn runs_match_brute_force(
+            positions in prop::collection::hash_set((-4..4, -4..4), 0..40),
+            letters in "[a-z]{40}",
+        ) {
+            let table = Table::new(
+                positions
+                    .into_iter()
+                    .zip(letters.chars())
+                    .map(|(position, character)| {
+                        (position.into(), Tile { team: 0, character })
+                    })
+                    .collect(),
+            ).unwrap();
+            for direction in [Direction::Horizontal, Direction::Vertical] {
+                let runs = table
+                    .get_runs(direction)
+                    .iter()
+                    .map(|w| (w.start(), w.get_word()))
+                    .collect::<Vec<_>>();
+                prop_assert_eq!(runs, runs_brute_force(&table, direction));
+            }
+            let words = table.get_words();
+            let letters = words.horizontal.iter().map(|w| w.tiles.len()).sum::<usize>();
+            prop_assert_eq!(letters, table.tiles().len());
+            let letters = words.vertical.iter().map(|w| w.tiles.len()).sum::<usize>();
+            prop_assert_eq!(letters, table.tiles().len());
+        }
+    }
+
+    #[test]
+    fn incremental_changes() {
+        let tile = |character| Tile { team: 0, character };
+        let word = |x, y, direction, word: &str| IndexedWord {
+            position: IVec2::new(x, y),
+            direction,
+            word: word.to_string(),
+        };
+        let mut table = Table::new(HashMap::from([
+            ((0, 0).into(), tile('c')),
+            ((1, 0).into(), tile('a')),
+            ((2, 0).into(), tile('t')),
+        ]))
+        .unwrap();
+        assert_eq!(table.indexed_words().count(), 1);
+
+        let changes = table.place_tile((3, 0).into(), tile('s')).unwrap();
+        assert_eq!(
+            changes.changed,
+            vec![(
+                word(0, 0, Direction::Horizontal, "cat"),
+                word(0, 0, Direction::Horizontal, "cats")
+            )]
+        );
+        assert!(changes.created.is_empty() && changes.destroyed.is_empty());
+
+        let changes = table.place_tile((1, 1).into(), tile('t')).unwrap();
+        assert_eq!(changes.created, vec![word(1, 0, Direction::Vertical, "at")]);
+        assert!(changes.changed.is_empty());
+
+        // "cats" is split into "c" and "ts", "at" loses its first letter.
+        let (removed, changes) = table.take_tile(IVec2::new(1, 0)).unwrap();
+        assert_eq!(removed.character, 'a');
+        assert_eq!(
+            changes.changed,
+            vec![(
+                word(0, 0, Direction::Horizontal, "cats"),
+                word(2, 0, Direction::Horizontal, "ts")
+            )]
+        );
+        assert_eq!(
+            changes.destroyed,
+            vec![word(1, 0, Direction::Vertical, "at")]
+        );
+        assert!(table.take_tile(IVec2::new(1, 0)).is_err());
+
+        assert!(table.move_tile(IVec2::new(0, 0), IVec2::new(2, 0)).is_err());
+        assert!(table.move_tile(IVec2::new(5, 5), IVec2::new(6, 6)).is_err());
+        let changes = table.move_tile(IVec2::new(1, 1), IVec2::new(1, 0)).unwrap();
+        assert_eq!(
+            changes.changed,
+            vec![(
+                word(2, 0, Direction::Horizontal, "ts"),
+                word(0, 0, Direction::Horizontal, "ctts")
+            )]
+        );
+        assert!(table.move_tile(IVec2::new(1, 0), IVec2::new(1, 0)).is_err());
+        let mut words = table.indexed_words().cloned().collect::<Vec<_>>();
+        words.sort_unstable_by_key(|w| w.word.clone());
+        assert_eq!(words, vec![word(0, 0, Direction::Horizontal, "ctts")]);
+    }
+
+    #[derive(Debug, Clone)]
+    enum Operation {
+        Place((i32, i32), char),
+        Take((i32, i32)),
+        Move((i32, i32), (i32, i32)),
+        Swap((i32, i32), (i32, i32)),
+    }
+
+    fn operation() -> impl Strategy<Value = Operation> {
+        let position = || (-3..3, -3..3);
+        prop_oneof![
+            (position(), prop::char::range('a', 'e')).prop_map(|(p, c)| Operation::Place(p, c)),
+            position().prop_map(Operation::Take),
+            (position(), position()).prop_map(|(from, to)| Operation::Move(from, to)),
+            (position(), position()).prop_map(|(a, b)| Operation::Swap(a, b)),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn index_matches_full_scan(operations in prop::collection::vec(operation(), 0..60)) {
+            let mut table = Table::new(HashMap::new()).unwrap();
+            for operation in operations {
+                let before = table.indexed_words().cloned().collect::<HashSet<_>>();
+                let tile_count = table.tiles().len();
+                let changes = match operation.clone() {
+                    Operation::Place(p, character) => {
+                        table.place_tile(p.into(), Tile { team: 0, character })
+                    }
+                    Operation::Take(p) => table.take_tile(p.into()).map(|(_, changes)| changes),
+                    Operation::Move(from, to) => table.move_tile(from.into(), to.into()),
+                    Operation::Swap(a, b) => table.swap_tiles(a.into(), b.into()),
+                };
+                if let (Operation::Move(..) | Operation::Swap(..), Ok(_)) = (&operation, &changes) {
+                    prop_assert_eq!(table.tiles().len(), tile_count);
+                }
+                let rescanned = Table::new(table.tiles().clone()).unwrap();
+                let after = table.indexed_words().cloned().collect::<HashSet<_>>();
+                prop_assert_eq!(&after, &rescanned.indexed_words().cloned().collect());
+
+                // Replaying the changes on the previous words gives the new ones.
+                let mut replayed = before;
+                if let Ok(changes) = changes {
+                    for (old, new) in changes.changed {
+                        prop_assert!(replayed.remove(&old));
+                        replayed.insert(new);
+                    }
+                    for old in changes.destroyed {
+                        prop_assert!(replayed.remove(&old));
+                    }
+                    replayed.extend(changes.created);
+                }
+                prop_assert_eq!(replayed, after);
+            }
+        }
+    }
+
+    #[test]
+    fn mutation_errors() {
+        let tile = |character| Tile { team: 0, character };
+        let (a, b, empty) = (IVec2::new(0, 0), IVec2::new(1, 0), IVec2::new(5, 5));
+        let mut table = Table::new(HashMap::from([(a, tile('o')), (b, tile('n'))])).unwrap();
+
+        assert_eq!(
+            table.place_tile(a, tile('x')).unwrap_err(),
+            TableError::Occupied(a)
+        );
+        let far = IVec2::new(0, MAX_COORDINATE + 1);
+        assert_eq!(
+            Table::new(HashMap::from([(IVec2::new(i32::MAX, 0), tile('x'))])).err(),
+            Some(TableError::OutOfBounds(IVec2::new(i32::MAX, 0)))
+        );
+        assert_eq!(
+            table.place_tile(far, tile('x')).unwrap_err(),
+            TableError::OutOfBounds(far)
+        );
+        assert_eq!(
+            table.take_tile(empty).unwrap_err(),
+            TableError::EmptySource(empty)
+        );
+        assert_eq!(
+            table.move_tile(empty, IVec2::new(6, 6)).unwrap_err(),
+            TableError::EmptySource(empty)
+        );
+        assert_eq!(table.move_tile(a, b).unwrap_err(), TableError::Occupied(b));
+        assert_eq!(
+            table.move_tile(a, far).unwrap_err(),
+            TableError::OutOfBounds(far)
+        );
+        assert_eq!(
+            table.swap_tiles(a, empty).unwrap_err(),
+            TableError::EmptySource(empty)
+        );
+        assert_eq!(table.tiles().len(), 2);
+        assert_eq!(table.words_at(a)[0].get_word(), "on");
+
+        let changes = table.swap_tiles(a, b).unwrap();
+        assert_eq!(changes.changed.len(), 1);
+        assert_eq!(changes.changed[0].1.word, "no");
+        assert!(table.swap_tiles(a, a).unwrap().is_empty());
+    }
+
+    #[test]
+    fn board_shape() {
+        let tile = |character| Tile { team: 0, character };
+        let (a, b) = (IVec2::new(0, 0), IVec2::new(1, 0));
+        let outside = IVec2::new(3, 0);
+        assert_eq!(
+            Table::with_shape(
+                HashMap::from([(outside, tile('x'))]),
+                BoardShape::grid(3, 3)
+            )
+            .err(),
+            Some(TableError::OutOfBounds(outside))
+        );
+
+        let tiles = HashMap::from([(a, tile('o')), (b, tile('n'))]);
+        let mut table = Table::with_shape(tiles, BoardShape::grid(3, 3)).unwrap();
+        assert_eq!(table.bounding_box(), Some(Bounds::new(a, b)));
+        assert!(table.is_on_board(IVec2::new(2, 2)));
+        assert!(!table.is_on_board(outside));
+        assert_eq!(
+            table.place_tile(outside, tile('x')).unwrap_err(),
+            TableError::OutOfBounds(outside)
+        );
+        assert_eq!(
+            table.move_tile(b, IVec2::new(0, -1)).unwrap_err(),
+            TableError::OutOfBounds(IVec2::new(0, -1))
+        );
+        table.move_tile(b, IVec2::new(2, 2)).unwrap();
+        assert_eq!(table.bounding_box(), Some(Bounds::new(a, IVec2::new(2, 2))));
+        let near_origin = Bounds::new(a, IVec2::ONE);
+        assert_eq!(table.tiles_in(near_origin).collect::<Vec<_>>().len(), 1);
+
+        let cross = BoardShape::from_rows(&[".#.", "###", ".#."]);
+        let mut table = Table::with_shape(HashMap::new(), cross).unwrap();
+        assert_eq!(table.bounding_box(), None);
+        assert_eq!(
+            table.shape().bounds(),
+            Some(Bounds::new(a, IVec2::new(2, 2)))
+        );
+        assert!(table.place_tile(a, tile('x')).is_err());
+        assert!(table.place_tile(IVec2::ONE, tile('x')).is_ok());
+    }
+
+    #[test]
+    fn premium_squares() {
+        let tile = |character| Tile { team: 0, character };
+        let (a, b, c) = (IVec2::new(0, 0), IVec2::new(1, 0), IVec2::new(2, 0));
+        let mut table = Table::new(HashMap::from([(a, tile('o'))])).unwrap();
+        table.set_premiums(HashMap::from([
+            (a, Premium::TripleWord),
+            (b, Premium::DoubleLetter),
+            (c, Premium::DoubleWord),
+        ]));
+        assert_eq!(table.premiums().len(), 2);
+
+        let changes = table.place_tile(b, tile('n')).unwrap();
+        assert_eq!(changes.premiums, vec![(b, Premium::DoubleLetter)]);
+        assert_eq!(changes.created[0].word, "on");
+        // Squares are only used by the first tile.
+        table.move_tile(b, IVec2::new(0, 1)).unwrap();
+        let changes = table.place_tile(b, tile('n')).unwrap();
+        assert!(changes.premiums.is_empty());
+
+        let changes = table.move_tile(IVec2::new(0, 1), c).unwrap();
+        assert_eq!(changes.premiums, vec![(c, Premium::DoubleWord)]);
+        assert!(table.premiums().is_empty());
+        table.swap_tiles(a, c).unwrap();
+        assert!(table.take_tile(c).unwrap().1.premiums.is_empty());
+
+        // A lone tile makes no word, using a premium is still a change.
+        let lone = IVec2::new(5, 5);
+        table.set_premiums(HashMap::from([(lone, Premium::TripleLetter)]));
+        assert!(!table.place_tile(lone, tile('x')).unwrap().is_empty());
     }
 }
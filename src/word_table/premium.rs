@@ -0,0 +1,245 @@
+//! Premium squares, multiplying the score of the first tile placed on them or of its words.
+//!
+//! Layouts are text files with one line per row and one whitespace separated token per
+//! square: `.` for a plain square, `DL`, `TL`, `DW` and `TW` for double and triple letter and
+//! word squares, and `L<n>`, `W<n>` or `L<n>W<m>` for other multipliers, one of them above 1.
+//! Lines starting with `#` are comments.
+
+use std::collections::HashMap;
+
+use glam::IVec2;
+
+use super::shape::Bounds;
+
+/// File extension of board layouts.
+pub const EXTENSION: &str = "board";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Premium {
+    DoubleLetter,
+    TripleLetter,
+    DoubleWord,
+    TripleWord,
+    Custom { letter: u32, word: u32 },
+}
+
+impl Premium {
+    /// The premium multiplying letters by `letter` and words by `word`, using the named
+    /// variants when there is one.
+    pub fn new(letter: u32, word: u32) -> Self {
+        match (letter, word) {
+            (2, 1) => Premium::DoubleLetter,
+            (3, 1) => Premium::TripleLetter,
+            (1, 2) => Premium::DoubleWord,
+            (1, 3) => Premium::TripleWord,
+            (letter, word) => Premium::Custom { letter, word },
+        }
+    }
+
+    /// Multiplier of the tile placed on the square.
+    pub fn letter_multiplier(&self) -> u32 {
+        match self {
+            Premium::DoubleLetter => 2,
+            Premium::TripleLetter => 3,
+            Premium::DoubleWord | Premium::TripleWord => 1,
+            Premium::Custom { letter, .. } => *letter,
+        }
+    }
+
+    /// Multiplier of the words going through the tile placed on the square.
+    pub fn word_multiplier(&self) -> u32 {
+        match self {
+            Premium::DoubleWord => 2,
+            Premium::TripleWord => 3,
+            Premium::DoubleLetter | Premium::TripleLetter => 1,
+            Premium::Custom { word, .. } => *word,
+        }
+    }
+
+    /// Reads a token of a layout, `None` for a plain square.
+    fn parse(token: &str) -> Result<Option<Premium>, ()> {
+        match token {
+            "." => return Ok(None),
+            "DL" => return Ok(Some(Premium::DoubleLetter)),
+            "TL" => return Ok(Some(Premium::TripleLetter)),
+            "DW" => return Ok(Some(Premium::DoubleWord)),
+            "TW" => return Ok(Some(Premium::TripleWord)),
+            "" => return Err(()),
+            _ => {}
+        }
+        let (letter, word) = match token.find('W') {
+            Some(i) => (&token[..i], Some(&token[i + 1..])),
+            None => (token, None),
+        };
+        let multiplier = |n: Option<&str>| match n {
+            None => Ok(1),
+            Some(n) => n.parse::<u32>().ok().filter(|n| *n > 0).ok_or(()),
+        };
+        let letter = match letter {
+            "" => None,
+            letter => Some(letter.strip_prefix('L').ok_or(())?),
+        };
+        match (multiplier(letter)?, multiplier(word)?) {
+            // Not a premium, plain squares are written `.`.
+            (1, 1) => Err(()),
+            (letter, word) => Ok(Some(Premium::new(letter, word))),
+        }
+    }
+}
+
+/// Same format as the layout files.
+impl std::fmt::Display for Premium {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Premium::DoubleLetter => write!(f, "DL"),
+            Premium::TripleLetter => write!(f, "TL"),
+            Premium::DoubleWord => write!(f, "DW"),
+            Premium::TripleWord => write!(f, "TW"),
+            Premium::Custom { letter, word } => {
+                if *letter != 1 {
+                    write!(f, "L{letter}")?;
+                }
+                if *word != 1 || *letter == 1 {
+                    write!(f, "W{word}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A token of a layout which is not a square.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayoutError {
+    /// Line of the token, starting at 1.
+    pub line: usize,
+    pub token: String,
+}
+
+impl std::fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: unknown square `{}`", self.line, self.token)
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+/// Premium squares of a board, the first row of the file is `y = 0` and its first token
+/// `x = 0`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PremiumLayout {
+    squares: HashMap<IVec2, Premium>,
+    size: IVec2,
+}
+
+impl PremiumLayout {
+    pub fn parse(text: &str) -> Result<Self, LayoutError> {
+        let mut layout = PremiumLayout::default();
+        let rows = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim_start().starts_with('#'));
+        for (y, (line, row)) in rows.enumerate() {
+            for (x, token) in row.split_whitespace().enumerate() {
+                let position = IVec2::new(x as i32, y as i32);
+                let premium = Premium::parse(token).map_err(|_| LayoutError {
+                    line: line + 1,
+                    token: token.to_string(),
+                })?;
+                if let Some(premium) = premium {
+                    layout.squares.insert(position, premium);
+                }
+                layout.size = layout.size.max(position + IVec2::ONE);
+            }
+        }
+        Ok(layout)
+    }
+
+    pub fn squares(&self) -> &HashMap<IVec2, Premium> {
+        &self.squares
+    }
+
+    /// Rectangle covered by the file, plain squares included, `None` if it is empty.
+    pub fn bounds(&self) -> Option<Bounds> {
+        (self.size.min_element() > 0).then(|| Bounds {
+            min: IVec2::ZERO,
+            max: self.size - IVec2::ONE,
+        })
+    }
+
+    /// Same as [`PremiumLayout::bounds`], moved like [`PremiumLayout::centered`].
+    pub fn centered_bounds(&self) -> Option<Bounds> {
+        let offset = self.size / 2;
+        self.bounds().map(|bounds| Bounds {
+            min: bounds.min - offset,
+            max: bounds.max - offset,
+        })
+    }
+
+    /// The squares moved so the middle square of the layout is at the origin, where the
+    /// first word of a game is placed.
+    pub fn centered(&self) -> HashMap<IVec2, Premium> {
+        let offset = self.size / 2;
+        self.squares
+            .iter()
+            .map(|(position, premium)| (*position - offset, *premium))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::IVec2;
+
+    use super::{Bounds, LayoutError, Premium, PremiumLayout};
+
+    #[test]
+    fn premiums() {
+        for token in ["DL", "TL", "DW", "TW", "L4", "W5", "L2W2"] {
+            let premium = Premium::parse(token).unwrap().unwrap();
+            assert_eq!(premium.to_string(), token);
+        }
+        assert_eq!(Premium::parse("L3W1"), Ok(Some(Premium::TripleLetter)));
+        assert_eq!(Premium::parse("."), Ok(None));
+        for token in [
+            "", "L", "W", "L0", "X2", "WL2", "W2L2", "L-1", "W1", "L1", "L1W1",
+        ] {
+            assert!(Premium::parse(token).is_err(), "{token}");
+        }
+        let custom = Premium::new(2, 3);
+        assert_eq!(custom.letter_multiplier(), 2);
+        assert_eq!(custom.word_multiplier(), 3);
+        assert_eq!(Premium::TripleWord.letter_multiplier(), 1);
+    }
+
+    #[test]
+    fn layout() {
+        let layout = PremiumLayout::parse("# corners\nTW . TW\n. DL\nTW . L2W4\n").unwrap();
+        assert_eq!(layout.squares().len(), 5);
+        assert_eq!(
+            layout.squares().get(&IVec2::new(1, 1)),
+            Some(&Premium::DoubleLetter)
+        );
+        assert_eq!(
+            layout.squares().get(&IVec2::new(2, 2)),
+            Some(&Premium::Custom { letter: 2, word: 4 })
+        );
+        assert_eq!(layout.bounds().unwrap().size(), IVec2::new(3, 3));
+        let centered = layout.centered();
+        assert_eq!(centered.get(&IVec2::ZERO), Some(&Premium::DoubleLetter));
+        assert_eq!(centered.get(&IVec2::NEG_ONE), Some(&Premium::TripleWord));
+        assert_eq!(
+            layout.centered_bounds(),
+            Some(Bounds::new(IVec2::NEG_ONE, IVec2::ONE))
+        );
+
+        assert_eq!(PremiumLayout::parse("").unwrap().bounds(), None);
+        assert_eq!(
+            PremiumLayout::parse("# x\n. .\n. XL\n").unwrap_err(),
+            LayoutError {
+                line: 3,
+                token: "XL".to_string()
+            }
+        );
+    }
+}
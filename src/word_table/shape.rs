@@ -0,0 +1,139 @@
+//! Where tiles can be placed: anywhere for freeform games, or on a fixed board.
+
+use std::collections::HashSet;
+
+use glam::IVec2;
+
+/// A rectangle of positions, both corners included.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Bounds {
+    pub min: IVec2,
+    pub max: IVec2,
+}
+
+impl Bounds {
+    /// The rectangle with `a` and `b` as opposite corners.
+    pub fn new(a: IVec2, b: IVec2) -> Self {
+        Bounds {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
+    /// Smallest rectangle containing all `positions`, `None` if there are none.
+    pub fn around<I: IntoIterator<Item = IVec2>>(positions: I) -> Option<Self> {
+        let mut positions = positions.into_iter();
+        let first = positions.next()?;
+        Some(
+            positions.fold(Bounds::new(first, first), |bounds, p| Bounds {
+                min: bounds.min.min(p),
+                max: bounds.max.max(p),
+            }),
+        )
+    }
+
+    pub fn contains(&self, position: IVec2) -> bool {
+        position.cmpge(self.min).all() && position.cmple(self.max).all()
+    }
+
+    /// Number of columns and rows.
+    pub fn size(&self) -> IVec2 {
+        self.max - self.min + IVec2::ONE
+    }
+
+    /// Positions row by row.
+    pub fn positions(&self) -> impl Iterator<Item = IVec2> {
+        let (min, max) = (self.min, self.max);
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+    }
+}
+
+/// Positions where tiles can be placed, see [`super::Table::with_shape`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum BoardShape {
+    /// Anywhere, as in Bananagrams.
+    #[default]
+    Unbounded,
+    /// Inside a rectangle, as on a Scrabble board.
+    Rect(Bounds),
+    /// Only at the given positions.
+    Mask(HashSet<IVec2>),
+}
+
+impl BoardShape {
+    /// A board of `columns` by `rows` positions, starting at the origin.
+    pub fn grid(columns: i32, rows: i32) -> Self {
+        BoardShape::Rect(Bounds {
+            min: IVec2::ZERO,
+            max: IVec2::new(columns, rows) - IVec2::ONE,
+        })
+    }
+
+    /// A board with a position for each `#` of `rows`, from the origin.
+    pub fn from_rows(rows: &[&str]) -> Self {
+        BoardShape::Mask(
+            rows.iter()
+                .enumerate()
+                .flat_map(|(y, row)| {
+                    row.chars()
+                        .enumerate()
+                        .filter(|(_, c)| *c == '#')
+                        .map(move |(x, _)| IVec2::new(x as i32, y as i32))
+                })
+                .collect(),
+        )
+    }
+
+    pub fn contains(&self, position: IVec2) -> bool {
+        match self {
+            BoardShape::Unbounded => true,
+            BoardShape::Rect(bounds) => bounds.contains(position),
+            BoardShape::Mask(positions) => positions.contains(&position),
+        }
+    }
+
+    /// Smallest rectangle containing the shape, `None` if it is unbounded or empty.
+    pub fn bounds(&self) -> Option<Bounds> {
+        match self {
+            BoardShape::Unbounded => None,
+            BoardShape::Rect(bounds) => Some(*bounds),
+            BoardShape::Mask(positions) => Bounds::around(positions.iter().copied()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::IVec2;
+
+    use super::{BoardShape, Bounds};
+
+    #[test]
+    fn shapes() {
+        let bounds = Bounds::new(IVec2::new(2, -1), IVec2::new(-1, 1));
+        assert_eq!(bounds.min, IVec2::new(-1, -1));
+        assert_eq!(bounds.size(), IVec2::new(4, 3));
+        assert_eq!(bounds.positions().count(), 12);
+        assert!(bounds.contains(IVec2::new(2, 1)));
+        assert!(!bounds.contains(IVec2::new(3, 0)));
+        assert_eq!(
+            Bounds::around([IVec2::new(1, 5), IVec2::new(-2, 0)]),
+            Some(Bounds::new(IVec2::new(-2, 0), IVec2::new(1, 5)))
+        );
+        assert_eq!(Bounds::around([]), None);
+
+        let scrabble = BoardShape::grid(15, 15);
+        assert!(scrabble.contains(IVec2::new(14, 0)));
+        assert!(!scrabble.contains(IVec2::new(15, 0)));
+        assert!(!scrabble.contains(IVec2::new(0, -1)));
+        assert_eq!(scrabble.bounds().unwrap().size(), IVec2::new(15, 15));
+
+        let cross = BoardShape::from_rows(&[".#.", "###", ".#."]);
+        assert!(cross.contains(IVec2::new(1, 1)));
+        assert!(!cross.contains(IVec2::new(0, 0)));
+        assert_eq!(cross.bounds(), BoardShape::grid(3, 3).bounds());
+
+        assert!(BoardShape::Unbounded.contains(IVec2::new(-100, 100)));
+        assert_eq!(BoardShape::Unbounded.bounds(), None);
+    }
+}
@@ -1,63 +1,217 @@
 use std::{collections::BTreeMap, io::BufRead};
 
+pub mod anagram;
+pub mod binary;
+pub mod dawg;
+pub mod fuzzy;
+pub mod gaddag;
+pub mod iter;
+pub mod merge;
+pub mod metadata;
+pub mod normalize;
+pub mod overlay;
+pub mod pattern;
+pub mod shared;
+pub mod stats;
+
+use fuzzy::Suggestion;
+use normalize::{LoadOptions, LoadReport};
+
+/// Read-only word queries, shared by the dictionary representations.
+pub trait Lexicon {
+    /// Returns true if `word` is a complete word.
+    fn contains(&self, word: &str) -> bool;
+    /// Returns true if at least one word starts with `prefix`.
+    fn is_prefix(&self, prefix: &str) -> bool;
+    /// Collects all words starting with `prefix`, in lexicographic order.
+    fn words_with_prefix(&self, prefix: &str) -> Vec<String>;
+    /// Words at most `max_distance` edits away from `word`, closest first, see [`fuzzy`].
+    ///
+    /// The default implementation compares `word` with every word.
+    fn suggestions(&self, word: &str, max_distance: usize) -> Vec<Suggestion> {
+        fuzzy::brute_force(self.words_with_prefix(""), word, max_distance)
+    }
+}
+
 pub struct PossibleWords {
     pub words_tree: WordTree,
 }
 
-#[derive(Default, Hash, Eq, PartialEq)]
+impl PossibleWords {
+    pub fn get_node(&self, prefix: &str) -> Option<&WordTree> {
+        self.words_tree.get_node(prefix)
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words_tree.contains(word)
+    }
+
+    pub fn is_prefix(&self, prefix: &str) -> bool {
+        self.words_tree.is_prefix(prefix)
+    }
+
+    pub fn words_with_prefix(&self, prefix: &str) -> Vec<String> {
+        self.words_tree.words_with_prefix(prefix)
+    }
+}
+
+impl Lexicon for PossibleWords {
+    fn contains(&self, word: &str) -> bool {
+        PossibleWords::contains(self, word)
+    }
+
+    fn is_prefix(&self, prefix: &str) -> bool {
+        PossibleWords::is_prefix(self, prefix)
+    }
+
+    fn words_with_prefix(&self, prefix: &str) -> Vec<String> {
+        PossibleWords::words_with_prefix(self, prefix)
+    }
+
+    fn suggestions(&self, word: &str, max_distance: usize) -> Vec<Suggestion> {
+        PossibleWords::suggestions(self, word, max_distance)
+    }
+}
+
+#[derive(Clone, Default, Hash, Eq, PartialEq)]
 pub struct WordTree {
     pub next: BTreeMap<char, Box<WordTree>>,
     pub can_be_last_letter: bool,
 }
 
 impl WordTree {
-    fn _visit<F>(&self, parents: Vec<char>, callback: &mut F)
+    fn visit_from<F>(&self, letters: &mut Vec<char>, callback: &mut F)
     where
         F: FnMut(&Vec<char>),
     {
         if self.can_be_last_letter {
-            callback(&parents);
+            callback(letters);
         }
-        for kv in self.next.iter() {
-            let mut new_parents = parents.clone();
-            new_parents.push(*kv.0);
-            kv.1._visit(new_parents, callback);
+        for (letter, child) in self.next.iter() {
+            letters.push(*letter);
+            child.visit_from(letters, callback);
+            letters.pop();
         }
     }
 
+    /// Calls `callback` with the letters of each word, in lexicographic order.
+    ///
+    /// See [`WordTree::words`] for an iterator.
     pub fn visit<F>(&self, callback: &mut F)
     where
         F: FnMut(&Vec<char>),
     {
-        self._visit(vec![], callback)
+        self.visit_from(&mut vec![], callback)
     }
-}
 
-pub fn load_from<B: BufRead>(reader: B) -> PossibleWords {
-    let mut tree_root = WordTree::default();
-    let lines = reader.lines();
-    for line in lines {
-        let Ok(line) = line else {
-        continue;
-       };
-        let mut tree_ref = &mut tree_root.next;
-        let length = line.len();
-        for (i, letter) in line.chars().enumerate() {
-            if let std::collections::btree_map::Entry::Vacant(e) = tree_ref.entry(letter) {
-                e.insert(Box::<WordTree>::default());
-            };
-            if i == length - 1 {
-                tree_ref.get_mut(&letter).unwrap().can_be_last_letter = true;
-            }
-            tree_ref = &mut tree_ref.get_mut(&letter).unwrap().next;
+    /// Follows `prefix` letter by letter, returning the node reached after its last letter.
+    ///
+    /// The empty prefix returns `self`.
+    pub fn get_node(&self, prefix: &str) -> Option<&WordTree> {
+        let mut node = self;
+        for letter in prefix.chars() {
+            node = node.next.get(&letter)?;
+        }
+        Some(node)
+    }
+
+    /// Returns true if `word` is a complete word of this tree.
+    pub fn contains(&self, word: &str) -> bool {
+        self.get_node(word)
+            .is_some_and(|node| node.can_be_last_letter)
+    }
+
+    /// Returns true if at least one word starts with `prefix` (words are their own prefix).
+    pub fn is_prefix(&self, prefix: &str) -> bool {
+        // Only the root can be a node without words, when the tree is empty.
+        self.get_node(prefix)
+            .is_some_and(|node| node.can_be_last_letter || !node.next.is_empty())
+    }
+
+    /// Collects all words starting with `prefix`, in lexicographic order.
+    pub fn words_with_prefix(&self, prefix: &str) -> Vec<String> {
+        self.words_from(prefix).collect()
+    }
+
+    /// Adds `word` below this node, the empty word is ignored.
+    pub fn insert(&mut self, word: &str) {
+        if word.is_empty() {
+            return;
+        }
+        let mut node = self;
+        for letter in word.chars() {
+            node = node.next.entry(letter).or_default();
         }
+        node.can_be_last_letter = true;
+    }
+
+    /// Removes `word` below this node, pruning branches which lead to no other word.
+    ///
+    /// Returns false if `word` was not in the tree.
+    pub fn remove(&mut self, word: &str) -> bool {
+        self.remove_letters(&word.chars().collect::<Vec<char>>())
+    }
+
+    fn remove_letters(&mut self, letters: &[char]) -> bool {
+        let Some((letter, rest)) = letters.split_first() else {
+            return std::mem::take(&mut self.can_be_last_letter);
+        };
+        let Some(child) = self.next.get_mut(letter) else {
+            return false;
+        };
+        let removed = child.remove_letters(rest);
+        if removed && !child.can_be_last_letter && child.next.is_empty() {
+            self.next.remove(letter);
+        }
+        removed
+    }
+
+    /// Number of nodes in this tree, including `self`.
+    pub fn node_count(&self) -> usize {
+        1 + self.next.values().map(|n| n.node_count()).sum::<usize>()
+    }
+}
+
+impl Lexicon for WordTree {
+    fn contains(&self, word: &str) -> bool {
+        WordTree::contains(self, word)
+    }
+
+    fn is_prefix(&self, prefix: &str) -> bool {
+        WordTree::is_prefix(self, prefix)
     }
-    PossibleWords {
-        words_tree: tree_root,
+
+    fn words_with_prefix(&self, prefix: &str) -> Vec<String> {
+        WordTree::words_with_prefix(self, prefix)
+    }
+
+    fn suggestions(&self, word: &str, max_distance: usize) -> Vec<Suggestion> {
+        WordTree::suggestions(self, word, max_distance)
     }
 }
 
+/// Loads a word list with the default [`LoadOptions`], see [`load_from_with`].
+pub fn load_from<B: BufRead>(reader: B) -> PossibleWords {
+    load_from_with(reader, &LoadOptions::default()).0
+}
+
+/// Loads one word per line, normalized by `options`.
+pub fn load_from_with<B: BufRead>(reader: B, options: &LoadOptions) -> (PossibleWords, LoadReport) {
+    let mut tree_root = WordTree::default();
+    let report = options.read_words(reader, |word| tree_root.insert(&word));
+    (
+        PossibleWords {
+            words_tree: tree_root,
+        },
+        report,
+    )
+}
+
+#[cfg(test)]
 mod test {
+    use std::collections::BTreeSet;
+
+    use proptest::prelude::*;
 
     #[test]
     fn parse() {
@@ -81,4 +235,130 @@ fantastic
         assert!(res.contains(&"fantastic".to_string()));
         assert_eq!(count, 5);
     }
+
+    #[test]
+    fn queries() {
+        let words = r#"hello
+world
+hell
+worms
+fantastic
+"#;
+        let tree_root = super::load_from(words.as_bytes());
+        assert!(tree_root.contains("hell"));
+        assert!(tree_root.contains("hello"));
+        assert!(!tree_root.contains("hel"));
+        assert!(!tree_root.contains("helloo"));
+        assert!(!tree_root.contains(""));
+
+        assert!(tree_root.is_prefix(""));
+        assert!(tree_root.is_prefix("hel"));
+        assert!(tree_root.is_prefix("hello"));
+        assert!(!tree_root.is_prefix("wa"));
+
+        assert_eq!(tree_root.words_with_prefix("hel"), vec!["hell", "hello"]);
+        assert_eq!(tree_root.words_with_prefix("wor"), vec!["world", "worms"]);
+        assert!(tree_root.words_with_prefix("x").is_empty());
+
+        let node = tree_root.get_node("fan").unwrap();
+        assert!(node.contains("tastic"));
+        assert!(tree_root.get_node("fax").is_none());
+
+        let empty = super::load_from("".as_bytes());
+        assert!(!empty.is_prefix(""));
+        assert!(empty.words_with_prefix("").is_empty());
+    }
+
+    #[test]
+    fn normalized() {
+        let words = "Hello\r\n\n# comment\n  \u{c9}t\u{c9}  \nhell\n";
+        let (tree_root, report) =
+            super::load_from_with(words.as_bytes(), &super::LoadOptions::default());
+        assert_eq!(
+            tree_root.words_with_prefix(""),
+            vec!["hell", "hello", "\u{e9}t\u{e9}"]
+        );
+        assert_eq!(report.accepted, 3);
+        assert_eq!(report.skipped, 2);
+        assert!(report.rejected.is_empty());
+    }
+
+    #[test]
+    fn remove() {
+        let mut tree_root = super::load_from("hell\nhello\nhelp\n".as_bytes());
+        assert!(tree_root.words_tree.remove("hello"));
+        assert!(!tree_root.words_tree.remove("hello"));
+        assert!(!tree_root.words_tree.remove("he"));
+        assert!(tree_root.contains("hell"));
+        assert!(!tree_root.is_prefix("hello"));
+
+        assert!(tree_root.words_tree.remove("hell"));
+        assert!(!tree_root.is_prefix("hell"));
+        assert!(tree_root.is_prefix("hel"), "still needed by \"help\"");
+        assert!(tree_root.words_tree.remove("help"));
+        assert!(tree_root.words_tree.next.is_empty());
+    }
+
+    /// Small alphabet with multi-byte letters, so random words share prefixes.
+    fn words() -> impl Strategy<Value = BTreeSet<String>> {
+        prop::collection::btree_set("[abcz\u{e9}\u{df}]{1,6}", 0..40)
+    }
+
+    fn tree_of(words: &BTreeSet<String>) -> super::WordTree {
+        let mut tree = super::WordTree::default();
+        for word in words {
+            tree.insert(word);
+        }
+        tree
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip(words in words()) {
+            let tree = tree_of(&words);
+            let mut visited = vec![];
+            tree.visit(&mut |letters| visited.push(letters.iter().collect::<String>()));
+            prop_assert_eq!(&visited, &words.iter().cloned().collect::<Vec<_>>());
+            prop_assert_eq!(tree.words().collect::<Vec<_>>(), visited);
+
+            let list = words.iter().map(|w| format!("{w}\n")).collect::<String>();
+            prop_assert!(super::load_from(list.as_bytes()).words_tree == tree);
+        }
+
+        #[test]
+        fn prefixes(words in words(), queries in prop::collection::vec("[abcz\u{e9}]{0,4}", 20)) {
+            let tree = tree_of(&words);
+            for query in &queries {
+                prop_assert_eq!(tree.contains(query), words.contains(query));
+                let expected = words
+                    .iter()
+                    .filter(|word| word.starts_with(query.as_str()))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                prop_assert_eq!(tree.is_prefix(query), !expected.is_empty());
+                prop_assert_eq!(tree.words_with_prefix(query), expected);
+            }
+        }
+
+        #[test]
+        fn any_bytes(bytes in prop::collection::vec(any::<u8>(), 0..200)) {
+            let tree = super::load_from(bytes.as_slice());
+            for word in tree.words() {
+                prop_assert!(!word.is_empty());
+                prop_assert!(tree.contains(&word));
+            }
+        }
+
+        #[test]
+        fn any_lines(text in "(\\PC|\n|\r\n| |\t|#){0,100}") {
+            let (tree, report) =
+                super::load_from_with(text.as_bytes(), &super::LoadOptions::default());
+            let words = tree.words().collect::<Vec<_>>();
+            prop_assert!(words.len() <= report.accepted);
+            for word in &words {
+                prop_assert!(!word.contains(char::is_whitespace));
+                prop_assert!(tree.contains(word));
+            }
+        }
+    }
 }
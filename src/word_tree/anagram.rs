@@ -0,0 +1,201 @@
+//! Words which can be formed from a rack of letters.
+
+use std::collections::BTreeMap;
+
+use super::{PossibleWords, WordTree};
+
+/// Letters of a [`Rack`] which can stand for any letter.
+pub const BLANKS: [char; 2] = ['?', '_'];
+
+/// A multiset of letters to form words with.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rack {
+    letters: BTreeMap<char, usize>,
+    blanks: usize,
+}
+
+impl Rack {
+    /// Letters are used as is, `?` and `_` are blanks.
+    pub fn new(letters: &str) -> Self {
+        let mut rack = Rack::default();
+        for letter in letters.chars() {
+            if BLANKS.contains(&letter) {
+                rack.blanks += 1;
+            } else {
+                *rack.letters.entry(letter).or_default() += 1;
+            }
+        }
+        rack
+    }
+
+    /// Number of letters, blanks included.
+    pub fn len(&self) -> usize {
+        self.letters.values().sum::<usize>() + self.blanks
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn blanks(&self) -> usize {
+        self.blanks
+    }
+}
+
+impl From<&str> for Rack {
+    fn from(value: &str) -> Self {
+        Rack::new(value)
+    }
+}
+
+/// A word formed from a [`Rack`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Anagram {
+    pub word: String,
+    /// Indices (in `char`s) of the letters played with a blank.
+    pub blanks: Vec<usize>,
+}
+
+struct Search<'a> {
+    rack: &'a mut Rack,
+    min_len: usize,
+    word: String,
+    depth: usize,
+    blanks: Vec<usize>,
+    res: Vec<Anagram>,
+}
+
+impl<'a> Search<'a> {
+    fn visit(&mut self, node: &WordTree) {
+        if node.can_be_last_letter && self.depth >= self.min_len {
+            self.res.push(Anagram {
+                word: self.word.clone(),
+                blanks: self.blanks.clone(),
+            });
+        }
+        for (letter, child) in node.next.iter() {
+            // A real letter is always preferred: the blank stays available for any letter,
+            // and each word is found once.
+            let count = self.rack.letters.get_mut(letter).filter(|c| **c > 0);
+            let use_blank = count.is_none();
+            if let Some(count) = count {
+                *count -= 1;
+            } else if self.rack.blanks > 0 {
+                self.rack.blanks -= 1;
+                self.blanks.push(self.depth);
+            } else {
+                continue;
+            }
+            self.word.push(*letter);
+            self.depth += 1;
+            self.visit(child);
+            self.depth -= 1;
+            self.word.pop();
+            if use_blank {
+                self.blanks.pop();
+                self.rack.blanks += 1;
+            } else {
+                *self.rack.letters.get_mut(letter).unwrap() += 1;
+            }
+        }
+    }
+}
+
+impl WordTree {
+    /// Words using all letters of `rack`, in lexicographic order.
+    pub fn anagrams(&self, rack: &Rack) -> Vec<Anagram> {
+        self.sub_anagrams(rack, rack.len())
+    }
+
+    /// Words using at least `min_len` letters of `rack`, in lexicographic order.
+    pub fn sub_anagrams(&self, rack: &Rack, min_len: usize) -> Vec<Anagram> {
+        let mut rack = rack.clone();
+        let mut search = Search {
+            rack: &mut rack,
+            min_len: min_len.max(1),
+            word: String::new(),
+            depth: 0,
+            blanks: vec![],
+            res: vec![],
+        };
+        search.visit(self);
+        search.res
+    }
+}
+
+impl PossibleWords {
+    pub fn anagrams(&self, rack: &Rack) -> Vec<Anagram> {
+        self.words_tree.anagrams(rack)
+    }
+
+    pub fn sub_anagrams(&self, rack: &Rack, min_len: usize) -> Vec<Anagram> {
+        self.words_tree.sub_anagrams(rack, min_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Anagram, Rack};
+    use crate::word_tree::load_from;
+
+    fn words(anagrams: Vec<Anagram>) -> Vec<String> {
+        anagrams.into_iter().map(|a| a.word).collect()
+    }
+
+    #[test]
+    fn rack() {
+        let rack = Rack::new("aab?_");
+        assert_eq!(rack.len(), 5);
+        assert_eq!(rack.blanks(), 2);
+        assert!(Rack::new("").is_empty());
+    }
+
+    #[test]
+    fn anagrams() {
+        let dictionary = load_from("act\ncat\ntac\ncats\nat\na\ntact\n".as_bytes());
+        assert_eq!(
+            words(dictionary.anagrams(&"tca".into())),
+            vec!["act", "cat", "tac"]
+        );
+        assert_eq!(
+            words(dictionary.sub_anagrams(&"tca".into(), 2)),
+            vec!["act", "at", "cat", "tac"]
+        );
+        assert_eq!(
+            words(dictionary.sub_anagrams(&"tca".into(), 0)),
+            vec!["a", "act", "at", "cat", "tac"]
+        );
+        assert!(dictionary.anagrams(&"tcaa".into()).is_empty());
+    }
+
+    #[test]
+    fn blanks() {
+        let dictionary = load_from("cat\ncats\ntact\ndog\n".as_bytes());
+        assert_eq!(
+            dictionary.anagrams(&"tca?".into()),
+            vec![
+                Anagram {
+                    word: "cats".to_string(),
+                    blanks: vec![3],
+                },
+                Anagram {
+                    word: "tact".to_string(),
+                    blanks: vec![3],
+                },
+            ]
+        );
+        assert_eq!(
+            dictionary.anagrams(&"???".into()),
+            vec![
+                Anagram {
+                    word: "cat".to_string(),
+                    blanks: vec![0, 1, 2],
+                },
+                Anagram {
+                    word: "dog".to_string(),
+                    blanks: vec![0, 1, 2],
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,580 @@
+//! Precompiled dictionary files.
+//!
+//! A compiled file is a [`Dawg`] laid out as flat little endian tables, so it can be
+//! queried directly from the loaded bytes without rebuilding any tree:
+//!
+//! | offset | size                  | content                                         |
+//! |--------|-----------------------|-------------------------------------------------|
+//! | 0      | 4                     | magic, `RSWD`                                   |
+//! | 4      | 2                     | format version, [`VERSION`]                     |
+//! | 6      | 2                     | reserved, 0                                     |
+//! | 8      | 4                     | alphabet length                                 |
+//! | 12     | 4                     | node count                                      |
+//! | 16     | 4                     | edge count                                      |
+//! | 20     | 4                     | root node index                                 |
+//! | 24     | 8                     | FNV-1a 64 checksum of everything after header   |
+//! | 32     | 4 * alphabet length   | alphabet, sorted `char` code points             |
+//! |        | 8 * node count        | nodes: first edge, edge count (bit 31: last letter) |
+//! |        | 8 * edge count        | edges: alphabet index, target node              |
+
+use std::{
+    fs::File,
+    io::{Read, Write},
+    path::Path,
+};
+
+use super::{
+    dawg::Dawg,
+    fuzzy::Suggestion,
+    load_from_with,
+    normalize::{LoadOptions, LoadReport},
+    Lexicon, PossibleWords,
+};
+
+pub const MAGIC: [u8; 4] = *b"RSWD";
+pub const VERSION: u16 = 1;
+/// File extension of compiled dictionaries.
+pub const EXTENSION: &str = "rswd";
+
+const HEADER_SIZE: usize = 32;
+const NODE_SIZE: usize = 8;
+const EDGE_SIZE: usize = 8;
+const LAST_LETTER_BIT: u32 = 1 << 31;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FormatError {
+    /// The file is smaller than its header or tables.
+    Truncated,
+    BadMagic,
+    UnsupportedVersion(u16),
+    ChecksumMismatch,
+    /// A table references an index outside of its bounds, an invalid `char`, or the graph
+    /// has a cycle.
+    Corrupted,
+}
+
+impl std::fmt::Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatError::Truncated => write!(f, "file is truncated"),
+            FormatError::BadMagic => write!(f, "not a compiled dictionary"),
+            FormatError::UnsupportedVersion(v) => {
+                write!(f, "unsupported version {v}, expected {VERSION}")
+            }
+            FormatError::ChecksumMismatch => write!(f, "checksum mismatch"),
+            FormatError::Corrupted => write!(f, "file is corrupted"),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf29ce484222325u64;
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+/// Serializes `dawg` to the compiled format.
+pub fn dawg_to_bytes(dawg: &Dawg) -> Vec<u8> {
+    let mut alphabet = dawg.edges.iter().map(|e| e.letter).collect::<Vec<char>>();
+    alphabet.sort_unstable();
+    alphabet.dedup();
+
+    let mut body = vec![];
+    for letter in &alphabet {
+        body.extend_from_slice(&(*letter as u32).to_le_bytes());
+    }
+    for node in &dawg.nodes {
+        let mut count = node.edge_count;
+        if node.can_be_last_letter {
+            count |= LAST_LETTER_BIT;
+        }
+        body.extend_from_slice(&node.first_edge.to_le_bytes());
+        body.extend_from_slice(&count.to_le_bytes());
+    }
+    for edge in &dawg.edges {
+        let letter = alphabet.binary_search(&edge.letter).unwrap() as u32;
+        body.extend_from_slice(&letter.to_le_bytes());
+        body.extend_from_slice(&edge.target.to_le_bytes());
+    }
+
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + body.len());
+    bytes.extend_from_slice(&MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.extend_from_slice(&0u16.to_le_bytes());
+    bytes.extend_from_slice(&(alphabet.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&(dawg.nodes.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&(dawg.edges.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&dawg.root.to_le_bytes());
+    bytes.extend_from_slice(&fnv1a(&body).to_le_bytes());
+    bytes.extend_from_slice(&body);
+    bytes
+}
+
+/// Serializes `words` to the compiled format.
+pub fn to_bytes(words: &PossibleWords) -> Vec<u8> {
+    dawg_to_bytes(&Dawg::from_tree(&words.words_tree))
+}
+
+pub fn write_to<W: Write>(words: &PossibleWords, mut writer: W) -> std::io::Result<()> {
+    writer.write_all(&to_bytes(words))
+}
+
+/// A compiled dictionary queried in place from a byte slice.
+#[derive(Clone, Copy)]
+pub struct DictionaryView<'a> {
+    alphabet: &'a [u8],
+    nodes: &'a [u8],
+    edges: &'a [u8],
+    root: u32,
+}
+
+/// Table boundaries of a validated compiled dictionary.
+#[derive(Clone, Copy)]
+struct Layout {
+    alphabet_end: usize,
+    nodes_end: usize,
+    root: u32,
+}
+
+impl Layout {
+    /// Checks the header, checksum and table bounds.
+    fn parse(bytes: &[u8]) -> Result<Layout, FormatError> {
+        if bytes.len() < HEADER_SIZE {
+            return Err(FormatError::Truncated);
+        }
+        if bytes[0..4] != MAGIC {
+            return Err(FormatError::BadMagic);
+        }
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != VERSION {
+            return Err(FormatError::UnsupportedVersion(version));
+        }
+        let alphabet_len = read_u32(bytes, 8) as usize;
+        let node_count = read_u32(bytes, 12) as usize;
+        let edge_count = read_u32(bytes, 16) as usize;
+        let root = read_u32(bytes, 20);
+        let checksum = u64::from_le_bytes(bytes[24..32].try_into().unwrap());
+
+        let alphabet_end = HEADER_SIZE + alphabet_len * 4;
+        let nodes_end = alphabet_end + node_count * NODE_SIZE;
+        let edges_end = nodes_end + edge_count * EDGE_SIZE;
+        if bytes.len() != edges_end {
+            return Err(FormatError::Truncated);
+        }
+        if fnv1a(&bytes[HEADER_SIZE..]) != checksum {
+            return Err(FormatError::ChecksumMismatch);
+        }
+        let layout = Layout {
+            alphabet_end,
+            nodes_end,
+            root,
+        };
+        layout.view(bytes).validate()?;
+        Ok(layout)
+    }
+
+    fn view<'a>(&self, bytes: &'a [u8]) -> DictionaryView<'a> {
+        DictionaryView {
+            alphabet: &bytes[HEADER_SIZE..self.alphabet_end],
+            nodes: &bytes[self.alphabet_end..self.nodes_end],
+            edges: &bytes[self.nodes_end..],
+            root: self.root,
+        }
+    }
+}
+
+impl<'a> DictionaryView<'a> {
+    /// Checks the header, checksum and table bounds once, queries don't validate again.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, FormatError> {
+        Ok(Layout::parse(bytes)?.view(bytes))
+    }
+
+    fn validate(&self) -> Result<(), FormatError> {
+        let (node_count, edge_count) = (self.node_count() as u32, self.edge_count() as u32);
+        // The builder always writes the root, even for an empty dictionary.
+        if self.root >= node_count {
+            return Err(FormatError::Corrupted);
+        }
+        for i in 0..self.alphabet.len() / 4 {
+            char::from_u32(read_u32(self.alphabet, i * 4)).ok_or(FormatError::Corrupted)?;
+        }
+        for i in 0..node_count {
+            let (first_edge, count, _) = self.node(i);
+            let Some(end) = first_edge
+                .checked_add(count)
+                .filter(|end| *end <= edge_count)
+            else {
+                return Err(FormatError::Corrupted);
+            };
+            // Nodes are written after their children, so edges pointing to a later node
+            // would allow cycles, and queries walking them would never end.
+            for edge in first_edge..end {
+                let offset = edge as usize * EDGE_SIZE;
+                let letter = read_u32(self.edges, offset) as usize;
+                let target = read_u32(self.edges, offset + 4);
+                if letter * 4 >= self.alphabet.len() || target >= i {
+                    return Err(FormatError::Corrupted);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len() / NODE_SIZE
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len() / EDGE_SIZE
+    }
+
+    /// Letters used by the dictionary, sorted.
+    pub fn alphabet(&self) -> impl Iterator<Item = char> + 'a {
+        self.alphabet
+            .chunks_exact(4)
+            .map(|c| char::from_u32(u32::from_le_bytes(c.try_into().unwrap())).unwrap())
+    }
+
+    fn node(&self, index: u32) -> (u32, u32, bool) {
+        let offset = index as usize * NODE_SIZE;
+        let first_edge = read_u32(self.nodes, offset);
+        let count = read_u32(self.nodes, offset + 4);
+        (
+            first_edge,
+            count & !LAST_LETTER_BIT,
+            count & LAST_LETTER_BIT != 0,
+        )
+    }
+
+    fn letter(&self, index: u32) -> char {
+        char::from_u32(read_u32(self.alphabet, index as usize * 4)).unwrap()
+    }
+
+    pub fn root(&self) -> CompiledNodeRef<'a> {
+        CompiledNodeRef {
+            view: *self,
+            index: self.root,
+        }
+    }
+
+    pub fn get_node(&self, prefix: &str) -> Option<CompiledNodeRef<'a>> {
+        let mut node = self.root();
+        for letter in prefix.chars() {
+            node = node.get(letter)?;
+        }
+        Some(node)
+    }
+}
+
+/// A node of a [`DictionaryView`].
+#[derive(Clone, Copy)]
+pub struct CompiledNodeRef<'a> {
+    view: DictionaryView<'a>,
+    index: u32,
+}
+
+impl<'a> CompiledNodeRef<'a> {
+    pub fn can_be_last_letter(&self) -> bool {
+        self.view.node(self.index).2
+    }
+
+    /// Outgoing edges, sorted by letter.
+    pub fn children(&self) -> impl Iterator<Item = (char, CompiledNodeRef<'a>)> + 'a {
+        let view = self.view;
+        let (first_edge, count, _) = view.node(self.index);
+        (first_edge..first_edge + count).map(move |edge| {
+            let offset = edge as usize * EDGE_SIZE;
+            let letter = view.letter(read_u32(view.edges, offset));
+            let index = read_u32(view.edges, offset + 4);
+            (letter, CompiledNodeRef { view, index })
+        })
+    }
+
+    pub fn get(&self, letter: char) -> Option<CompiledNodeRef<'a>> {
+        self.children()
+            .find(|(l, _)| *l == letter)
+            .map(|(_, node)| node)
+    }
+
+    fn collect_words(&self, word: &mut String, res: &mut Vec<String>) {
+        if self.can_be_last_letter() {
+            res.push(word.clone());
+        }
+        for (letter, child) in self.children() {
+            word.push(letter);
+            child.collect_words(word, res);
+            word.pop();
+        }
+    }
+}
+
+impl<'a> Lexicon for DictionaryView<'a> {
+    fn contains(&self, word: &str) -> bool {
+        self.get_node(word)
+            .is_some_and(|node| node.can_be_last_letter())
+    }
+
+    fn is_prefix(&self, prefix: &str) -> bool {
+        self.get_node(prefix)
+            .is_some_and(|node| node.can_be_last_letter() || node.children().next().is_some())
+    }
+
+    fn words_with_prefix(&self, prefix: &str) -> Vec<String> {
+        let mut res = vec![];
+        if let Some(node) = self.get_node(prefix) {
+            node.collect_words(&mut prefix.to_string(), &mut res);
+        }
+        res
+    }
+
+    fn suggestions(&self, word: &str, max_distance: usize) -> Vec<Suggestion> {
+        DictionaryView::suggestions(self, word, max_distance)
+    }
+}
+
+/// Owned bytes of a validated compiled dictionary.
+pub struct CompiledDictionary {
+    bytes: Vec<u8>,
+    layout: Layout,
+}
+
+impl CompiledDictionary {
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, FormatError> {
+        let layout = Layout::parse(&bytes)?;
+        Ok(Self { bytes, layout })
+    }
+
+    pub fn view(&self) -> DictionaryView<'_> {
+        self.layout.view(&self.bytes)
+    }
+}
+
+impl Lexicon for CompiledDictionary {
+    fn contains(&self, word: &str) -> bool {
+        self.view().contains(word)
+    }
+
+    fn is_prefix(&self, prefix: &str) -> bool {
+        self.view().is_prefix(prefix)
+    }
+
+    fn words_with_prefix(&self, prefix: &str) -> Vec<String> {
+        self.view().words_with_prefix(prefix)
+    }
+
+    fn suggestions(&self, word: &str, max_distance: usize) -> Vec<Suggestion> {
+        CompiledDictionary::suggestions(self, word, max_distance)
+    }
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(std::io::Error),
+    Format(FormatError),
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "could not read dictionary: {e}"),
+            LoadError::Format(e) => write!(f, "invalid compiled dictionary: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<std::io::Error> for LoadError {
+    fn from(value: std::io::Error) -> Self {
+        LoadError::Io(value)
+    }
+}
+
+impl From<FormatError> for LoadError {
+    fn from(value: FormatError) -> Self {
+        LoadError::Format(value)
+    }
+}
+
+/// A dictionary loaded either from a word list or from a compiled file.
+pub enum LoadedDictionary {
+    Words(PossibleWords),
+    Compiled(CompiledDictionary),
+}
+
+impl LoadedDictionary {
+    /// Loads compiled bytes, or parses them as a word list when they lack the compiled magic.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, FormatError> {
+        Ok(Self::from_bytes_with(bytes, &LoadOptions::default())?.0)
+    }
+
+    /// Same as [`LoadedDictionary::from_bytes`], word lists are normalized by `options`.
+    ///
+    /// The report is empty for compiled dictionaries, they were normalized when compiled.
+    pub fn from_bytes_with(
+        bytes: Vec<u8>,
+        options: &LoadOptions,
+    ) -> Result<(Self, LoadReport), FormatError> {
+        if bytes.starts_with(&MAGIC) {
+            Ok((
+                LoadedDictionary::Compiled(CompiledDictionary::from_bytes(bytes)?),
+                LoadReport::default(),
+            ))
+        } else {
+            let (words, report) = load_from_with(bytes.as_slice(), options);
+            Ok((LoadedDictionary::Words(words), report))
+        }
+    }
+
+    fn lexicon(&self) -> &dyn Lexicon {
+        match self {
+            LoadedDictionary::Words(words) => words,
+            LoadedDictionary::Compiled(compiled) => compiled,
+        }
+    }
+}
+
+impl Lexicon for LoadedDictionary {
+    fn contains(&self, word: &str) -> bool {
+        self.lexicon().contains(word)
+    }
+
+    fn is_prefix(&self, prefix: &str) -> bool {
+        self.lexicon().is_prefix(prefix)
+    }
+
+    fn words_with_prefix(&self, prefix: &str) -> Vec<String> {
+        self.lexicon().words_with_prefix(prefix)
+    }
+
+    fn suggestions(&self, word: &str, max_distance: usize) -> Vec<Suggestion> {
+        self.lexicon().suggestions(word, max_distance)
+    }
+}
+
+/// Opens a dictionary file, compiled or a word list whatever its extension, see
+/// [`LoadedDictionary::from_bytes`].
+pub fn open<P: AsRef<Path>>(path: P) -> Result<LoadedDictionary, LoadError> {
+    let mut bytes = vec![];
+    File::open(path)?.read_to_end(&mut bytes)?;
+    Ok(LoadedDictionary::from_bytes(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompiledDictionary, DictionaryView, FormatError, LoadedDictionary};
+    use crate::word_tree::Lexicon;
+
+    const WORDS: &str = r#"walking
+talking
+walk
+talk
+tall
+eat
+a
+"#;
+
+    #[test]
+    fn round_trip() {
+        let words = crate::word_tree::load_from(WORDS.as_bytes());
+        let bytes = super::to_bytes(&words);
+        let view = DictionaryView::new(&bytes).unwrap();
+        assert_eq!(view.alphabet().collect::<String>(), "aegiklntw".to_string());
+        for prefix in ["", "wal", "talk", "eat", "x"] {
+            assert_eq!(
+                view.words_with_prefix(prefix),
+                words.words_with_prefix(prefix),
+                "{prefix}"
+            );
+        }
+        assert!(view.contains("tall"));
+        assert!(!view.contains("tal"));
+        assert!(view.is_prefix("tal"));
+    }
+
+    #[test]
+    fn empty_dictionary() {
+        let words = crate::word_tree::load_from("".as_bytes());
+        let compiled = CompiledDictionary::from_bytes(super::to_bytes(&words)).unwrap();
+        assert!(!compiled.contains("a"));
+        assert!(compiled.words_with_prefix("").is_empty());
+        for prefix in ["", "a"] {
+            assert_eq!(
+                compiled.is_prefix(prefix),
+                words.is_prefix(prefix),
+                "{prefix}"
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_files() {
+        let words = crate::word_tree::load_from(WORDS.as_bytes());
+        let bytes = super::to_bytes(&words);
+
+        assert_eq!(
+            DictionaryView::new(&bytes[..10]).err(),
+            Some(FormatError::Truncated)
+        );
+        assert_eq!(
+            DictionaryView::new(&bytes[..bytes.len() - 1]).err(),
+            Some(FormatError::Truncated)
+        );
+        let mut bad_version = bytes.clone();
+        bad_version[4] = 42;
+        assert_eq!(
+            DictionaryView::new(&bad_version).err(),
+            Some(FormatError::UnsupportedVersion(42))
+        );
+        let mut flipped = bytes.clone();
+        *flipped.last_mut().unwrap() ^= 1;
+        assert_eq!(
+            DictionaryView::new(&flipped).err(),
+            Some(FormatError::ChecksumMismatch)
+        );
+
+        // The root is written last, with the last edges: point one of them back to it.
+        let mut cyclic = bytes.clone();
+        let root = cyclic[20..24].to_vec();
+        let len = cyclic.len();
+        cyclic[len - 4..].copy_from_slice(&root);
+        let checksum = super::fnv1a(&cyclic[super::HEADER_SIZE..]);
+        cyclic[24..32].copy_from_slice(&checksum.to_le_bytes());
+        assert_eq!(
+            DictionaryView::new(&cyclic).err(),
+            Some(FormatError::Corrupted)
+        );
+
+        // Without its root node, an empty dictionary would have nothing to query.
+        let mut rootless = super::to_bytes(&crate::word_tree::load_from("".as_bytes()));
+        rootless.truncate(super::HEADER_SIZE);
+        rootless[12..16].copy_from_slice(&0u32.to_le_bytes());
+        let checksum = super::fnv1a(&[]);
+        rootless[24..32].copy_from_slice(&checksum.to_le_bytes());
+        assert_eq!(
+            DictionaryView::new(&rootless).err(),
+            Some(FormatError::Corrupted)
+        );
+    }
+
+    #[test]
+    fn text_fallback() {
+        let loaded = LoadedDictionary::from_bytes(WORDS.as_bytes().to_vec()).unwrap();
+        assert!(matches!(loaded, LoadedDictionary::Words(_)));
+        assert!(loaded.contains("walking"));
+
+        let words = crate::word_tree::load_from(WORDS.as_bytes());
+        let loaded = LoadedDictionary::from_bytes(super::to_bytes(&words)).unwrap();
+        assert!(matches!(loaded, LoadedDictionary::Compiled(_)));
+        assert!(loaded.contains("walking"));
+    }
+}
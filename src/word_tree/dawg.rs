@@ -0,0 +1,400 @@
+//! Minimized directed acyclic word graph.
+//!
+//! A [`WordTree`] shares prefixes only: "walking" and "talking" end with two separate
+//! `alking` chains. A [`Dawg`] also shares suffixes, and stores all nodes and edges
+//! in two flat vectors instead of one `BTreeMap` allocation per node.
+
+use std::{collections::HashMap, io::BufRead};
+
+use super::{
+    fuzzy::Suggestion,
+    normalize::{LoadOptions, LoadReport},
+    Lexicon, WordTree,
+};
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub(crate) struct DawgEdge {
+    pub letter: char,
+    pub target: u32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct DawgNode {
+    pub first_edge: u32,
+    pub edge_count: u32,
+    pub can_be_last_letter: bool,
+}
+
+pub struct Dawg {
+    pub(crate) nodes: Vec<DawgNode>,
+    /// Edges of a node are contiguous and sorted by letter.
+    pub(crate) edges: Vec<DawgEdge>,
+    pub(crate) root: u32,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DawgError {
+    /// Words must be inserted in strictly increasing `char` order.
+    Unsorted { previous: String, word: String },
+}
+
+impl std::fmt::Display for DawgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DawgError::Unsorted { previous, word } => {
+                write!(f, "\"{word}\" was inserted after \"{previous}\"")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DawgError {}
+
+/// A node of a [`Dawg`], borrowed from it.
+#[derive(Clone, Copy)]
+pub struct DawgNodeRef<'a> {
+    dawg: &'a Dawg,
+    index: u32,
+}
+
+impl<'a> DawgNodeRef<'a> {
+    pub fn can_be_last_letter(&self) -> bool {
+        self.dawg.nodes[self.index as usize].can_be_last_letter
+    }
+
+    /// Outgoing edges, sorted by letter.
+    pub fn children(&self) -> impl Iterator<Item = (char, DawgNodeRef<'a>)> + 'a {
+        let dawg = self.dawg;
+        dawg.node_edges(self.index).iter().map(move |edge| {
+            (
+                edge.letter,
+                DawgNodeRef {
+                    dawg,
+                    index: edge.target,
+                },
+            )
+        })
+    }
+
+    pub fn get(&self, letter: char) -> Option<DawgNodeRef<'a>> {
+        // The root has an edge per first letter, the edges are sorted so search them.
+        let edges = self.dawg.node_edges(self.index);
+        let i = edges.binary_search_by_key(&letter, |e| e.letter).ok()?;
+        Some(DawgNodeRef {
+            dawg: self.dawg,
+            index: edges[i].target,
+        })
+    }
+
+    /// Follows `prefix` from this node, see [`WordTree::get_node`].
+    pub fn get_node(&self, prefix: &str) -> Option<DawgNodeRef<'a>> {
+        let mut node = *self;
+        for letter in prefix.chars() {
+            node = node.get(letter)?;
+        }
+        Some(node)
+    }
+
+    fn collect_words(&self, word: &mut String, res: &mut Vec<String>) {
+        if self.can_be_last_letter() {
+            res.push(word.clone());
+        }
+        for (letter, child) in self.children() {
+            word.push(letter);
+            child.collect_words(word, res);
+            word.pop();
+        }
+    }
+}
+
+impl Dawg {
+    pub fn root(&self) -> DawgNodeRef<'_> {
+        DawgNodeRef {
+            dawg: self,
+            index: self.root,
+        }
+    }
+
+    pub fn get_node(&self, prefix: &str) -> Option<DawgNodeRef<'_>> {
+        self.root().get_node(prefix)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Approximate heap memory used by the graph, in bytes.
+    pub fn heap_size(&self) -> usize {
+        self.nodes.capacity() * std::mem::size_of::<DawgNode>()
+            + self.edges.capacity() * std::mem::size_of::<DawgEdge>()
+    }
+
+    fn node_edges(&self, index: u32) -> &[DawgEdge] {
+        let node = &self.nodes[index as usize];
+        let start = node.first_edge as usize;
+        &self.edges[start..start + node.edge_count as usize]
+    }
+
+    /// Builds a graph from words sorted by `char` order, duplicates are ignored.
+    pub fn from_sorted_words<I, S>(words: I) -> Result<Dawg, DawgError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut builder = DawgBuilder::default();
+        for word in words {
+            builder.insert(word.as_ref())?;
+        }
+        Ok(builder.finish())
+    }
+
+    /// Builds a graph from words in any order.
+    pub fn from_words<I, S>(words: I) -> Dawg
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut words = words
+            .into_iter()
+            .map(|w| w.as_ref().to_string())
+            .collect::<Vec<String>>();
+        words.sort_unstable();
+        Dawg::from_sorted_words(words).expect("words were sorted")
+    }
+
+    /// Builds a graph accepting the same words as `tree`.
+    pub fn from_tree(tree: &WordTree) -> Dawg {
+        let mut builder = DawgBuilder::default();
+        tree.visit(&mut |word| {
+            builder
+                .insert(&word.iter().collect::<String>())
+                .expect("WordTree visits words in order")
+        });
+        builder.finish()
+    }
+}
+
+impl Lexicon for Dawg {
+    fn contains(&self, word: &str) -> bool {
+        self.get_node(word)
+            .is_some_and(|node| node.can_be_last_letter())
+    }
+
+    fn is_prefix(&self, prefix: &str) -> bool {
+        self.get_node(prefix)
+            .is_some_and(|node| node.can_be_last_letter() || node.children().next().is_some())
+    }
+
+    fn words_with_prefix(&self, prefix: &str) -> Vec<String> {
+        let mut res = vec![];
+        if let Some(node) = self.get_node(prefix) {
+            node.collect_words(&mut prefix.to_string(), &mut res);
+        }
+        res
+    }
+
+    fn suggestions(&self, word: &str, max_distance: usize) -> Vec<Suggestion> {
+        Dawg::suggestions(self, word, max_distance)
+    }
+}
+
+#[derive(Default)]
+struct PendingNode {
+    can_be_last_letter: bool,
+    edges: Vec<DawgEdge>,
+}
+
+/// Incremental construction from sorted input (Daciuk et al., 2000).
+///
+/// Only the path of the last inserted word is kept mutable (`pending`); every node
+/// before it can no longer change, so it is merged with an identical registered node
+/// as soon as it leaves that path.
+struct DawgBuilder {
+    nodes: Vec<DawgNode>,
+    edges: Vec<DawgEdge>,
+    register: HashMap<(bool, Vec<DawgEdge>), u32>,
+    /// `pending[i]` is the node reached after `previous[..i]`.
+    pending: Vec<PendingNode>,
+    previous: Vec<char>,
+}
+
+impl Default for DawgBuilder {
+    fn default() -> Self {
+        Self {
+            nodes: vec![],
+            edges: vec![],
+            register: HashMap::new(),
+            pending: vec![PendingNode::default()],
+            previous: vec![],
+        }
+    }
+}
+
+impl DawgBuilder {
+    fn insert(&mut self, word: &str) -> Result<(), DawgError> {
+        let word = word.chars().collect::<Vec<char>>();
+        if word.is_empty() || word == self.previous {
+            return Ok(());
+        }
+        if word < self.previous {
+            return Err(DawgError::Unsorted {
+                previous: self.previous.iter().collect(),
+                word: word.iter().collect(),
+            });
+        }
+        let common = word
+            .iter()
+            .zip(self.previous.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        self.freeze_until(common + 1);
+        for _ in common..word.len() {
+            self.pending.push(PendingNode::default());
+        }
+        self.pending.last_mut().unwrap().can_be_last_letter = true;
+        self.previous = word;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Dawg {
+        self.freeze_until(1);
+        let root = self.pending.pop().unwrap();
+        let root = self.freeze(root);
+        self.nodes.shrink_to_fit();
+        self.edges.shrink_to_fit();
+        Dawg {
+            nodes: self.nodes,
+            edges: self.edges,
+            root,
+        }
+    }
+
+    /// Freezes pending nodes until only `len` remain.
+    fn freeze_until(&mut self, len: usize) {
+        while self.pending.len() > len {
+            let node = self.pending.pop().unwrap();
+            let target = self.freeze(node);
+            let letter = self.previous[self.pending.len() - 1];
+            self.pending
+                .last_mut()
+                .unwrap()
+                .edges
+                .push(DawgEdge { letter, target });
+        }
+    }
+
+    fn freeze(&mut self, node: PendingNode) -> u32 {
+        let key = (node.can_be_last_letter, node.edges);
+        if let Some(index) = self.register.get(&key) {
+            return *index;
+        }
+        let index = self.nodes.len() as u32;
+        self.nodes.push(DawgNode {
+            first_edge: self.edges.len() as u32,
+            edge_count: key.1.len() as u32,
+            can_be_last_letter: key.0,
+        });
+        self.edges.extend_from_slice(&key.1);
+        self.register.insert(key, index);
+        index
+    }
+}
+
+/// Same input format as [`super::load_from`], the input does not need to be sorted.
+pub fn load_from<B: BufRead>(reader: B) -> Dawg {
+    load_from_with(reader, &LoadOptions::default()).0
+}
+
+/// Same as [`super::load_from_with`].
+pub fn load_from_with<B: BufRead>(reader: B, options: &LoadOptions) -> (Dawg, LoadReport) {
+    let mut words = vec![];
+    let report = options.read_words(reader, |word| words.push(word));
+    words.sort_unstable();
+    let dawg = Dawg::from_sorted_words(words).expect("words were sorted");
+    (dawg, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dawg, DawgError};
+    use crate::word_tree::Lexicon;
+
+    const WORDS: &str = r#"walking
+talking
+walk
+talk
+tall
+wall
+walls
+talks
+a
+"#;
+
+    #[test]
+    fn same_words_as_tree() {
+        let tree = crate::word_tree::load_from(WORDS.as_bytes());
+        let dawg = super::load_from(WORDS.as_bytes());
+        let mut tree_words = vec![];
+        tree.words_tree
+            .visit(&mut |w| tree_words.push(w.iter().collect::<String>()));
+        assert_eq!(dawg.words_with_prefix(""), tree_words);
+        for word in ["walk", "talks", "a", "tal", "walking", "wallz", ""] {
+            assert_eq!(dawg.contains(word), tree.contains(word), "{word}");
+            assert_eq!(dawg.is_prefix(word), tree.is_prefix(word), "{word}");
+            assert_eq!(
+                dawg.words_with_prefix(word),
+                tree.words_with_prefix(word),
+                "{word}"
+            );
+        }
+        assert!(dawg.node_count() < tree.words_tree.node_count());
+    }
+
+    #[test]
+    fn empty_dictionary() {
+        let tree = crate::word_tree::load_from("".as_bytes());
+        let dawg = super::load_from("".as_bytes());
+        for word in ["", "a"] {
+            assert_eq!(dawg.contains(word), tree.contains(word), "{word}");
+            assert_eq!(dawg.is_prefix(word), tree.is_prefix(word), "{word}");
+        }
+        assert!(dawg.words_with_prefix("").is_empty());
+    }
+
+    #[test]
+    fn suffixes_are_shared() {
+        let dawg = Dawg::from_words(["walking", "talking"]);
+        // The root, then a single chain reached by both 'w' and 't'.
+        assert_eq!(dawg.node_count(), 8);
+        let w = dawg.get_node("wa").unwrap();
+        let t = dawg.get_node("ta").unwrap();
+        assert_eq!(w.index, t.index);
+    }
+
+    #[test]
+    fn unsorted_input() {
+        assert_eq!(
+            Dawg::from_sorted_words(["b", "a"]).err(),
+            Some(DawgError::Unsorted {
+                previous: "b".to_string(),
+                word: "a".to_string()
+            })
+        );
+        let dawg = Dawg::from_words(["b", "a", "b"]);
+        assert_eq!(dawg.words_with_prefix(""), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn from_tree() {
+        let tree = crate::word_tree::load_from(WORDS.as_bytes());
+        let dawg = Dawg::from_tree(&tree.words_tree);
+        let mut tree_words = vec![];
+        tree.words_tree
+            .visit(&mut |w| tree_words.push(w.iter().collect::<String>()));
+        assert_eq!(dawg.words_with_prefix(""), tree_words);
+    }
+}
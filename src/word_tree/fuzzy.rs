@@ -0,0 +1,273 @@
+//! "Did you mean" suggestions: words within a few edits of a misspelled one.
+//!
+//! Edits are letter substitutions, insertions, deletions and transpositions of two adjacent
+//! letters (optimal string alignment distance). Tree walks keep one row of the distance
+//! matrix per letter and skip branches which can only get further than the allowed distance.
+
+use super::{
+    binary::{CompiledDictionary, CompiledNodeRef, DictionaryView},
+    dawg::{Dawg, DawgNodeRef},
+    PossibleWords, WordTree,
+};
+
+/// A word near the searched one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Suggestion {
+    pub word: String,
+    /// Number of edits from the searched word, 0 when it is itself a word.
+    pub distance: usize,
+}
+
+/// Number of edits to turn `a` into `b`, see the [module documentation](self).
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<char>>();
+    let mut rows = vec![(0..=b.len()).collect::<Vec<usize>>()];
+    let mut letters = vec![];
+    for letter in a.chars() {
+        let row = next_row(&rows, &letters, &b, letter);
+        letters.push(letter);
+        rows.push(row);
+    }
+    rows.last().unwrap()[b.len()]
+}
+
+/// Row of the distance matrix after appending `letter` to `letters`, `rows` ends with the
+/// row of `letters`.
+fn next_row(rows: &[Vec<usize>], letters: &[char], target: &[char], letter: char) -> Vec<usize> {
+    let previous = &rows[rows.len() - 1];
+    let mut row = Vec::with_capacity(previous.len());
+    row.push(previous[0] + 1);
+    for j in 1..previous.len() {
+        let cost = usize::from(target[j - 1] != letter);
+        let mut distance = (previous[j] + 1)
+            .min(row[j - 1] + 1)
+            .min(previous[j - 1] + cost);
+        if j > 1 && letters.last() == Some(&target[j - 1]) && letter == target[j - 2] {
+            distance = distance.min(rows[rows.len() - 2][j - 2] + 1);
+        }
+        row.push(distance);
+    }
+    row
+}
+
+/// Sorts by distance then word, and removes duplicate words.
+pub(crate) fn rank(suggestions: &mut Vec<Suggestion>) {
+    suggestions.sort_by(|a, b| a.distance.cmp(&b.distance).then(a.word.cmp(&b.word)));
+    suggestions.dedup_by(|a, b| a.word == b.word);
+}
+
+/// Compares `word` with every word of `words`, for dictionaries which can't be walked.
+pub(crate) fn brute_force(
+    words: impl IntoIterator<Item = String>,
+    word: &str,
+    max_distance: usize,
+) -> Vec<Suggestion> {
+    let mut res = words
+        .into_iter()
+        .filter_map(|candidate| {
+            let distance = edit_distance(word, &candidate);
+            (distance <= max_distance).then_some(Suggestion {
+                word: candidate,
+                distance,
+            })
+        })
+        .collect();
+    rank(&mut res);
+    res
+}
+
+/// A node of a dictionary tree, walked one letter at a time.
+trait Node: Copy {
+    fn is_word(self) -> bool;
+    fn for_each_child<F: FnMut(char, Self)>(self, f: F);
+}
+
+impl Node for &WordTree {
+    fn is_word(self) -> bool {
+        self.can_be_last_letter
+    }
+
+    fn for_each_child<F: FnMut(char, Self)>(self, mut f: F) {
+        for (letter, child) in self.next.iter() {
+            f(*letter, child);
+        }
+    }
+}
+
+impl<'a> Node for DawgNodeRef<'a> {
+    fn is_word(self) -> bool {
+        self.can_be_last_letter()
+    }
+
+    fn for_each_child<F: FnMut(char, Self)>(self, mut f: F) {
+        for (letter, child) in self.children() {
+            f(letter, child);
+        }
+    }
+}
+
+impl<'a> Node for CompiledNodeRef<'a> {
+    fn is_word(self) -> bool {
+        self.can_be_last_letter()
+    }
+
+    fn for_each_child<F: FnMut(char, Self)>(self, mut f: F) {
+        for (letter, child) in self.children() {
+            f(letter, child);
+        }
+    }
+}
+
+struct Search {
+    target: Vec<char>,
+    max_distance: usize,
+    letters: Vec<char>,
+    /// One row of the distance matrix per letter of `letters`, plus the first one.
+    rows: Vec<Vec<usize>>,
+    res: Vec<Suggestion>,
+}
+
+impl Search {
+    fn visit<N: Node>(&mut self, node: N) {
+        let row = &self.rows[self.rows.len() - 1];
+        let distance = row[self.target.len()];
+        if node.is_word() && distance <= self.max_distance {
+            self.res.push(Suggestion {
+                word: self.letters.iter().collect(),
+                distance,
+            });
+        }
+        // Distances of longer words come from this row, or from the previous one through
+        // a transposition, which costs one more edit.
+        let reachable = row.iter().min().copied().unwrap_or_default().min(
+            self.rows
+                .len()
+                .checked_sub(2)
+                .and_then(|i| self.rows[i].iter().min())
+                .map_or(usize::MAX, |d| d + 1),
+        );
+        if reachable > self.max_distance {
+            return;
+        }
+        node.for_each_child(|letter, child| {
+            let row = next_row(&self.rows, &self.letters, &self.target, letter);
+            self.rows.push(row);
+            self.letters.push(letter);
+            self.visit(child);
+            self.letters.pop();
+            self.rows.pop();
+        });
+    }
+}
+
+fn search<N: Node>(root: N, word: &str, max_distance: usize) -> Vec<Suggestion> {
+    let target = word.chars().collect::<Vec<char>>();
+    let mut search = Search {
+        rows: vec![(0..=target.len()).collect()],
+        target,
+        max_distance,
+        letters: vec![],
+        res: vec![],
+    };
+    search.visit(root);
+    rank(&mut search.res);
+    search.res
+}
+
+impl WordTree {
+    /// Words at most `max_distance` edits away from `word`, closest first.
+    pub fn suggestions(&self, word: &str, max_distance: usize) -> Vec<Suggestion> {
+        search(self, word, max_distance)
+    }
+}
+
+impl PossibleWords {
+    pub fn suggestions(&self, word: &str, max_distance: usize) -> Vec<Suggestion> {
+        self.words_tree.suggestions(word, max_distance)
+    }
+}
+
+impl Dawg {
+    /// See [`WordTree::suggestions`].
+    pub fn suggestions(&self, word: &str, max_distance: usize) -> Vec<Suggestion> {
+        search(self.root(), word, max_distance)
+    }
+}
+
+impl<'a> DictionaryView<'a> {
+    /// See [`WordTree::suggestions`].
+    pub fn suggestions(&self, word: &str, max_distance: usize) -> Vec<Suggestion> {
+        search(self.root(), word, max_distance)
+    }
+}
+
+impl CompiledDictionary {
+    pub fn suggestions(&self, word: &str, max_distance: usize) -> Vec<Suggestion> {
+        self.view().suggestions(word, max_distance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{brute_force, edit_distance, Suggestion};
+    use crate::word_tree::{binary, dawg::Dawg, load_from};
+
+    #[test]
+    fn distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("cat", "cat"), 0);
+        assert_eq!(edit_distance("cat", "bat"), 1);
+        assert_eq!(edit_distance("cat", "cart"), 1);
+        assert_eq!(edit_distance("cart", "cat"), 1);
+        assert_eq!(edit_distance("cat", "act"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        // Letters can't be edited again after a transposition.
+        assert_eq!(edit_distance("ca", "abc"), 3);
+    }
+
+    const WORDS: &str = "cat\ncart\nact\nbat\ncast\ncoat\ndog\ncats\nat\n";
+
+    fn words(suggestions: &[Suggestion]) -> Vec<(&str, usize)> {
+        suggestions
+            .iter()
+            .map(|s| (s.word.as_str(), s.distance))
+            .collect()
+    }
+
+    #[test]
+    fn suggestions() {
+        let tree = load_from(WORDS.as_bytes());
+        assert_eq!(words(&tree.suggestions("cta", 1)), vec![("cat", 1)],);
+        assert_eq!(
+            words(&tree.suggestions("cat", 1)),
+            vec![
+                ("cat", 0),
+                ("act", 1),
+                ("at", 1),
+                ("bat", 1),
+                ("cart", 1),
+                ("cast", 1),
+                ("cats", 1),
+                ("coat", 1)
+            ],
+        );
+        assert!(tree.suggestions("xyzzy", 2).is_empty());
+        assert_eq!(words(&tree.suggestions("", 2)), vec![("at", 2)]);
+    }
+
+    #[test]
+    fn same_as_brute_force() {
+        let tree = load_from(WORDS.as_bytes());
+        let dawg = Dawg::from_tree(&tree.words_tree);
+        let compiled = binary::CompiledDictionary::from_bytes(binary::to_bytes(&tree)).unwrap();
+        for word in ["", "c", "ca", "tac", "dgo", "coast", "carts", "zzz"] {
+            for max_distance in 0..4 {
+                let expected = brute_force(tree.words_with_prefix(""), word, max_distance);
+                assert_eq!(tree.suggestions(word, max_distance), expected);
+                assert_eq!(dawg.suggestions(word, max_distance), expected);
+                assert_eq!(compiled.suggestions(word, max_distance), expected);
+            }
+        }
+    }
+}
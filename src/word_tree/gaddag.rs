@@ -0,0 +1,211 @@
+//! Index of words by their inner letters, to extend words on both ends.
+//!
+//! A GADDAG stores each word once per letter: for `cat`, `c>at`, `ac>t` and `tac>`, where `>`
+//! stands for [`SEPARATOR`]. The letters before the separator are read backwards from the split
+//! point, so any infix of a word, reversed, is a path from the root. The paths are stored in
+//! a [`Dawg`] to share their common suffixes.
+
+use std::io::BufRead;
+
+use super::{
+    dawg::{Dawg, DawgNodeRef},
+    normalize::{LoadOptions, LoadReport},
+    WordTree,
+};
+
+/// Ends the reversed part of a path, words containing it are ignored.
+pub const SEPARATOR: char = '\0';
+
+pub struct Gaddag {
+    dawg: Dawg,
+}
+
+impl Gaddag {
+    /// Builds the index of `words`, in any order.
+    pub fn from_words<I, S>(words: I) -> Gaddag
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut paths = vec![];
+        for word in words {
+            let word = word.as_ref();
+            if word.contains(SEPARATOR) {
+                continue;
+            }
+            let letters = word.chars().collect::<Vec<char>>();
+            for split in 1..=letters.len() {
+                let mut path = String::with_capacity(word.len() + 1);
+                path.extend(letters[..split].iter().rev());
+                path.push(SEPARATOR);
+                path.extend(&letters[split..]);
+                paths.push(path);
+            }
+        }
+        paths.sort_unstable();
+        Gaddag {
+            dawg: Dawg::from_sorted_words(paths).expect("paths were sorted"),
+        }
+    }
+
+    pub fn from_tree(tree: &WordTree) -> Gaddag {
+        Gaddag::from_words(tree.words())
+    }
+
+    /// Node reached by reading `letters` backwards from the root.
+    fn reversed(&self, letters: &str) -> Option<DawgNodeRef<'_>> {
+        let mut node = self.dawg.root();
+        for letter in letters.chars().rev() {
+            node = node.get(letter)?;
+        }
+        Some(node)
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        !word.is_empty()
+            && self
+                .reversed(word)
+                .and_then(|node| node.get(SEPARATOR))
+                .is_some_and(|node| node.can_be_last_letter())
+    }
+
+    /// Letters which can be placed before `word` to form another word, sorted.
+    pub fn front_hooks(&self, word: &str) -> Vec<char> {
+        let Some(node) = self.reversed(word) else {
+            return vec![];
+        };
+        node.children()
+            .filter(|(letter, child)| {
+                *letter != SEPARATOR
+                    && child
+                        .get(SEPARATOR)
+                        .is_some_and(|end| end.can_be_last_letter())
+            })
+            .map(|(letter, _)| letter)
+            .collect()
+    }
+
+    /// Letters which can be placed after `word` to form another word, sorted.
+    pub fn back_hooks(&self, word: &str) -> Vec<char> {
+        let Some(node) = self.reversed(word).and_then(|node| node.get(SEPARATOR)) else {
+            return vec![];
+        };
+        node.children()
+            .filter(|(_, child)| child.can_be_last_letter())
+            .map(|(letter, _)| letter)
+            .collect()
+    }
+
+    /// Words containing `infix`, in lexicographic order.
+    pub fn words_containing(&self, infix: &str) -> Vec<String> {
+        let mut res = vec![];
+        if let Some(node) = self.reversed(infix) {
+            collect_words(node, &mut vec![], &mut infix.to_string(), &mut res);
+        }
+        // A word is found once per occurrence of `infix`.
+        res.sort_unstable();
+        res.dedup();
+        res
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.dawg.node_count()
+    }
+
+    /// Approximate heap memory used by the index, in bytes.
+    pub fn heap_size(&self) -> usize {
+        self.dawg.heap_size()
+    }
+}
+
+/// Words below `node`: `before` holds the letters read backwards after the infix, `after`
+/// starts with the infix and gets the letters after the separator.
+fn collect_words(
+    node: DawgNodeRef,
+    before: &mut Vec<char>,
+    after: &mut String,
+    res: &mut Vec<String>,
+) {
+    for (letter, child) in node.children() {
+        if letter == SEPARATOR {
+            let prefix = before.iter().rev().collect::<String>();
+            collect_suffixes(child, &prefix, after, res);
+        } else {
+            before.push(letter);
+            collect_words(child, before, after, res);
+            before.pop();
+        }
+    }
+}
+
+fn collect_suffixes(node: DawgNodeRef, prefix: &str, word: &mut String, res: &mut Vec<String>) {
+    if node.can_be_last_letter() {
+        res.push(format!("{prefix}{word}"));
+    }
+    for (letter, child) in node.children() {
+        word.push(letter);
+        collect_suffixes(child, prefix, word, res);
+        word.pop();
+    }
+}
+
+/// Same input format as [`super::load_from`].
+pub fn load_from<B: BufRead>(reader: B) -> Gaddag {
+    load_from_with(reader, &LoadOptions::default()).0
+}
+
+/// Same as [`super::load_from_with`].
+pub fn load_from_with<B: BufRead>(reader: B, options: &LoadOptions) -> (Gaddag, LoadReport) {
+    let mut words = vec![];
+    let report = options.read_words(reader, |word| words.push(word));
+    (Gaddag::from_words(words), report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_from, Gaddag};
+
+    const WORDS: &str = "at\ncat\nchat\nchats\ncats\nscat\nact\nacts\ntact\n";
+
+    #[test]
+    fn hooks() {
+        let gaddag = load_from(WORDS.as_bytes());
+        assert!(gaddag.contains("cat"));
+        assert!(gaddag.contains("scat"));
+        assert!(!gaddag.contains("ca"));
+        assert!(!gaddag.contains(""));
+
+        assert_eq!(gaddag.front_hooks("at"), vec!['c']);
+        assert_eq!(gaddag.front_hooks("cat"), vec!['s']);
+        assert_eq!(gaddag.front_hooks("act"), vec!['t']);
+        assert!(gaddag.front_hooks("dog").is_empty());
+
+        assert_eq!(gaddag.back_hooks("cat"), vec!['s']);
+        assert_eq!(gaddag.back_hooks("ca"), vec!['t']);
+        assert!(gaddag.back_hooks("cats").is_empty());
+    }
+
+    #[test]
+    fn infixes() {
+        let gaddag = load_from(WORDS.as_bytes());
+        assert_eq!(
+            gaddag.words_containing("at"),
+            vec!["at", "cat", "cats", "chat", "chats", "scat"]
+        );
+        assert_eq!(gaddag.words_containing("ct"), vec!["act", "acts", "tact"]);
+        assert_eq!(gaddag.words_containing("tact"), vec!["tact"]);
+        assert!(gaddag.words_containing("xyz").is_empty());
+        assert_eq!(gaddag.words_containing("").len(), 9);
+    }
+
+    #[test]
+    fn from_tree() {
+        let tree = crate::word_tree::load_from(WORDS.as_bytes());
+        let gaddag = Gaddag::from_tree(&tree.words_tree);
+        assert_eq!(gaddag.words_containing(""), tree.words_with_prefix(""));
+        // Words containing the separator are ignored.
+        let gaddag = Gaddag::from_words(["tat", "at", "t\0t"]);
+        assert!(gaddag.contains("tat"));
+        assert_eq!(gaddag.words_containing(""), vec!["at", "tat"]);
+    }
+}
@@ -0,0 +1,157 @@
+//! Lazy traversal of the words of a [`WordTree`].
+
+use std::collections::btree_map;
+
+use super::{PossibleWords, WordTree};
+
+type Children<'a> = btree_map::Iter<'a, char, Box<WordTree>>;
+
+/// Words of a [`WordTree`], in lexicographic order, see [`WordTree::words`].
+///
+/// Letters are kept in a single buffer: [`Words::next_word`] borrows it without allocating,
+/// the [`Iterator`] implementation copies it into a new `String` for each word.
+pub struct Words<'a> {
+    /// Children left to visit, one iterator per letter of `word` below the starting node.
+    stack: Vec<Children<'a>>,
+    word: String,
+    /// The starting node is itself a word which was not returned yet.
+    start_is_word: bool,
+    max_depth: Option<usize>,
+}
+
+impl<'a> Words<'a> {
+    /// Words below `start`, each prefixed by `prefix`.
+    pub(super) fn new(start: Option<&'a WordTree>, prefix: &str) -> Self {
+        Words {
+            stack: start.map(|node| node.next.iter()).into_iter().collect(),
+            word: prefix.to_string(),
+            start_is_word: start.is_some_and(|node| node.can_be_last_letter),
+            max_depth: None,
+        }
+    }
+
+    /// Only returns words of at most `depth` letters below the starting node.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        if depth == 0 {
+            self.stack.clear();
+        }
+        self
+    }
+
+    /// Advances to the next word, borrowing it until the following call.
+    pub fn next_word(&mut self) -> Option<&str> {
+        if std::mem::take(&mut self.start_is_word) {
+            return Some(&self.word);
+        }
+        while let Some(children) = self.stack.last_mut() {
+            let Some((letter, child)) = children.next() else {
+                if self.stack.pop().is_some() && !self.stack.is_empty() {
+                    self.word.pop();
+                }
+                continue;
+            };
+            let depth = self.stack.len();
+            // Keeps one iterator per letter below the start, even for leaves or at the
+            // depth limit, so that popping it also pops its letter.
+            if self.max_depth.is_none_or(|max| depth < max) {
+                self.stack.push(child.next.iter());
+            } else {
+                self.stack.push(Children::default());
+            }
+            self.word.push(*letter);
+            if child.can_be_last_letter {
+                return Some(&self.word);
+            }
+        }
+        None
+    }
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.next_word().map(str::to_string)
+    }
+}
+
+impl WordTree {
+    /// Iterates over the words of this tree, in lexicographic order.
+    pub fn words(&self) -> Words<'_> {
+        Words::new(Some(self), "")
+    }
+
+    /// Iterates over the words starting with `prefix`, in lexicographic order.
+    pub fn words_from(&self, prefix: &str) -> Words<'_> {
+        Words::new(self.get_node(prefix), prefix)
+    }
+}
+
+impl PossibleWords {
+    pub fn words(&self) -> Words<'_> {
+        self.words_tree.words()
+    }
+
+    pub fn words_from(&self, prefix: &str) -> Words<'_> {
+        self.words_tree.words_from(prefix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::word_tree::load_from;
+
+    const WORDS: &str = "hello\nworld\nhell\nworms\nhe\nfantastic\nh\n";
+
+    #[test]
+    fn lexicographic_order() {
+        let words = load_from(WORDS.as_bytes());
+        assert_eq!(
+            words.words().collect::<Vec<_>>(),
+            vec!["fantastic", "h", "he", "hell", "hello", "world", "worms"]
+        );
+        assert_eq!(
+            words.words_from("hel").collect::<Vec<_>>(),
+            vec!["hell", "hello"]
+        );
+        assert_eq!(
+            words.words_from("he").collect::<Vec<_>>(),
+            vec!["he", "hell", "hello"]
+        );
+        assert_eq!(words.words_from("x").count(), 0);
+        assert_eq!(load_from("".as_bytes()).words().count(), 0);
+    }
+
+    #[test]
+    fn depth_and_early_termination() {
+        let words = load_from(WORDS.as_bytes());
+        assert_eq!(
+            words.words().max_depth(4).collect::<Vec<_>>(),
+            vec!["h", "he", "hell"]
+        );
+        assert_eq!(
+            words.words_from("he").max_depth(2).collect::<Vec<_>>(),
+            vec!["he", "hell"]
+        );
+        assert_eq!(
+            words.words_from("he").max_depth(0).collect::<Vec<_>>(),
+            vec!["he"]
+        );
+        assert_eq!(
+            words.words_from("wor").take(1).collect::<Vec<_>>(),
+            vec!["world"]
+        );
+    }
+
+    #[test]
+    fn borrowed_buffer() {
+        let words = load_from(WORDS.as_bytes());
+        let mut iter = words.words_from("h");
+        let mut lengths = vec![];
+        while let Some(word) = iter.next_word() {
+            lengths.push(word.len());
+        }
+        assert_eq!(lengths, vec![1, 2, 4, 5]);
+    }
+}
@@ -0,0 +1,225 @@
+//! Combinations of several dictionaries into one, and differences between two of them.
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    io::{self, Write},
+};
+
+use super::{iter::Words, Lexicon, PossibleWords, WordTree};
+
+/// How several dictionaries are combined into one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Combine {
+    /// Words of any of the dictionaries.
+    #[default]
+    Union,
+    /// Words of all of the dictionaries.
+    Intersection,
+}
+
+/// Combines the words of `lexicons` into a new dictionary.
+pub fn combine(lexicons: &[&dyn Lexicon], mode: Combine) -> PossibleWords {
+    let mut tree_root = WordTree::default();
+    match mode {
+        Combine::Union => {
+            for lexicon in lexicons {
+                for word in lexicon.words_with_prefix("") {
+                    tree_root.insert(&word);
+                }
+            }
+        }
+        Combine::Intersection => {
+            if let Some((first, others)) = lexicons.split_first() {
+                for word in first.words_with_prefix("") {
+                    if others.iter().all(|other| other.contains(&word)) {
+                        tree_root.insert(&word);
+                    }
+                }
+            }
+        }
+    }
+    PossibleWords {
+        words_tree: tree_root,
+    }
+}
+
+/// Words of `base` which are in none of `removed`, into a new dictionary.
+pub fn subtract(base: &dyn Lexicon, removed: &[&dyn Lexicon]) -> PossibleWords {
+    let mut tree_root = WordTree::default();
+    for word in base.words_with_prefix("") {
+        if !removed.iter().any(|other| other.contains(&word)) {
+            tree_root.insert(&word);
+        }
+    }
+    PossibleWords {
+        words_tree: tree_root,
+    }
+}
+
+/// Words which differ between two dictionaries, in lexicographic order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Diff {
+    /// Words only in the new dictionary.
+    pub added: Vec<String>,
+    /// Words only in the old dictionary.
+    pub removed: Vec<String>,
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// One `+word` or `-word` line per change, sorted by word.
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut added = self.added.iter().peekable();
+        let mut removed = self.removed.iter().peekable();
+        loop {
+            let take_added = match (added.peek(), removed.peek()) {
+                (Some(a), Some(r)) => a < r,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => return Ok(()),
+            };
+            if take_added {
+                writeln!(f, "+{}", added.next().unwrap())?;
+            } else {
+                writeln!(f, "-{}", removed.next().unwrap())?;
+            }
+        }
+    }
+}
+
+impl WordTree {
+    /// Words added and removed from `self` to `other`, walking both trees at once so that
+    /// shared branches are only visited once.
+    pub fn diff(&self, other: &WordTree) -> Diff {
+        let mut diff = Diff::default();
+        diff_nodes(self, other, &mut String::new(), &mut diff);
+        diff
+    }
+}
+
+impl PossibleWords {
+    /// See [`WordTree::diff`].
+    pub fn diff(&self, other: &PossibleWords) -> Diff {
+        self.words_tree.diff(&other.words_tree)
+    }
+}
+
+fn diff_nodes(old: &WordTree, new: &WordTree, word: &mut String, diff: &mut Diff) {
+    match (old.can_be_last_letter, new.can_be_last_letter) {
+        (true, false) => diff.removed.push(word.clone()),
+        (false, true) => diff.added.push(word.clone()),
+        _ => {}
+    }
+    // Both children maps are sorted, so they are joined like two sorted lists.
+    let mut old_children = old.next.iter().peekable();
+    let mut new_children = new.next.iter().peekable();
+    loop {
+        let order = match (old_children.peek(), new_children.peek()) {
+            (Some((a, _)), Some((b, _))) => a.cmp(b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => return,
+        };
+        match order {
+            Ordering::Less => {
+                let (letter, child) = old_children.next().unwrap();
+                push_words(child, *letter, word, &mut diff.removed);
+            }
+            Ordering::Greater => {
+                let (letter, child) = new_children.next().unwrap();
+                push_words(child, *letter, word, &mut diff.added);
+            }
+            Ordering::Equal => {
+                let (letter, old_child) = old_children.next().unwrap();
+                let (_, new_child) = new_children.next().unwrap();
+                word.push(*letter);
+                diff_nodes(old_child, new_child, word, diff);
+                word.pop();
+            }
+        }
+    }
+}
+
+/// Pushes the words of the branch `node`, reached from `prefix` through `letter`.
+fn push_words(node: &WordTree, letter: char, prefix: &str, res: &mut Vec<String>) {
+    res.extend(Words::new(Some(node), &format!("{prefix}{letter}")));
+}
+
+/// Writes `words` as a word list, one word per line, which [`super::load_from`] reads back.
+pub fn write_words<W: Write>(words: &PossibleWords, mut writer: W) -> io::Result<()> {
+    let mut iter = words.words();
+    while let Some(word) = iter.next_word() {
+        writeln!(writer, "{word}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{combine, subtract, write_words, Combine, Diff};
+    use crate::word_tree::{dawg::Dawg, load_from};
+
+    #[test]
+    fn union_and_intersection() {
+        let a = load_from("cat\ndog\nbird\n".as_bytes());
+        let b = Dawg::from_words(["dog", "cat", "fish"]);
+        let c = load_from("cat\nfish\n".as_bytes());
+
+        let union = combine(&[&a, &b, &c], Combine::Union);
+        assert_eq!(
+            union.words_with_prefix(""),
+            vec!["bird", "cat", "dog", "fish"]
+        );
+        let intersection = combine(&[&a, &b], Combine::Intersection);
+        assert_eq!(intersection.words_with_prefix(""), vec!["cat", "dog"]);
+        let intersection = combine(&[&a, &b, &c], Combine::Intersection);
+        assert_eq!(intersection.words_with_prefix(""), vec!["cat"]);
+        assert!(combine(&[], Combine::Intersection)
+            .words_with_prefix("")
+            .is_empty());
+    }
+
+    #[test]
+    fn difference() {
+        let a = load_from("cat\ndog\nbird\n".as_bytes());
+        let b = Dawg::from_words(["dog", "fish"]);
+        let c = load_from("bird\n".as_bytes());
+        assert_eq!(
+            subtract(&a, &[&b]).words_with_prefix(""),
+            vec!["bird", "cat"]
+        );
+        assert_eq!(subtract(&a, &[&b, &c]).words_with_prefix(""), vec!["cat"]);
+        assert_eq!(
+            subtract(&a, &[]).words_with_prefix(""),
+            vec!["bird", "cat", "dog"]
+        );
+    }
+
+    #[test]
+    fn diff() {
+        let old = load_from("cat\ncats\ndog\nbird\nbi\n".as_bytes());
+        let new = load_from("cat\ndo\ndog\ndogs\nbird\nfish\nfishes\n".as_bytes());
+        let diff = old.diff(&new);
+        assert_eq!(diff.added, vec!["do", "dogs", "fish", "fishes"]);
+        assert_eq!(diff.removed, vec!["bi", "cats"]);
+        assert_eq!(diff.to_string(), "-bi\n-cats\n+do\n+dogs\n+fish\n+fishes\n");
+        assert_eq!(new.diff(&old).added, diff.removed);
+        assert!(old.diff(&old).is_empty());
+        assert_eq!(Diff::default().to_string(), "");
+    }
+
+    #[test]
+    fn write_and_load() {
+        let words = load_from("dog\ncat\ncats\n".as_bytes());
+        let mut bytes = vec![];
+        write_words(&words, &mut bytes).unwrap();
+        assert_eq!(bytes, b"cat\ncats\ndog\n");
+        assert!(words.diff(&load_from(bytes.as_slice())).is_empty());
+    }
+}
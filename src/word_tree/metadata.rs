@@ -0,0 +1,193 @@
+//! Optional information about words, loaded from a sidecar file next to a word list.
+//!
+//! The sidecar has one word per line, followed by these tab separated fields, which may be
+//! empty or missing: part of speech, frequency rank, sources (comma separated), definition.
+
+use std::{collections::HashMap, fmt, io::BufRead};
+
+use super::{
+    normalize::{LoadOptions, LoadReport, RejectReason},
+    Lexicon,
+};
+
+/// Extension of sidecar files, which share the name of their word list.
+pub const EXTENSION: &str = "tsv";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WordInfo {
+    pub part_of_speech: Option<String>,
+    /// 1 for the most frequent word.
+    pub frequency_rank: Option<u32>,
+    /// Word lists or references the word comes from.
+    pub sources: Vec<String>,
+    pub definition: Option<String>,
+}
+
+impl WordInfo {
+    fn parse(fields: &[&str]) -> Result<Self, RejectReason> {
+        let field = |i: usize| fields.get(i).map(|f| f.trim()).filter(|f| !f.is_empty());
+        let frequency_rank = match field(1) {
+            Some(rank) => Some(
+                rank.parse()
+                    .map_err(|_| RejectReason::InvalidField("frequency rank"))?,
+            ),
+            None => None,
+        };
+        Ok(WordInfo {
+            part_of_speech: field(0).map(str::to_string),
+            frequency_rank,
+            sources: field(2)
+                .map(|sources| {
+                    sources
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            definition: field(3).map(str::to_string),
+        })
+    }
+}
+
+/// `noun, rank 12: definition (source, source)`, leaving out missing fields.
+impl fmt::Display for WordInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut details = vec![];
+        if let Some(part_of_speech) = &self.part_of_speech {
+            details.push(part_of_speech.clone());
+        }
+        if let Some(rank) = self.frequency_rank {
+            details.push(format!("rank {rank}"));
+        }
+        write!(f, "{}", details.join(", "))?;
+        if let Some(definition) = &self.definition {
+            if !details.is_empty() {
+                write!(f, ": ")?;
+            }
+            write!(f, "{definition}")?;
+        }
+        if !self.sources.is_empty() {
+            write!(f, " ({})", self.sources.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// [`WordInfo`] by word.
+#[derive(Default)]
+pub struct WordMetadata {
+    entries: HashMap<String, WordInfo>,
+}
+
+impl WordMetadata {
+    /// Loads a sidecar file, words are normalized by `options`.
+    ///
+    /// When a word appears on several lines, the last one wins.
+    pub fn load_from<B: BufRead>(reader: B, options: &LoadOptions) -> (Self, LoadReport) {
+        let mut metadata = WordMetadata::default();
+        let report = LoadOptions::read_entries(
+            reader,
+            |line| {
+                let line = line.strip_suffix('\r').unwrap_or(line);
+                let fields = line.split('\t').collect::<Vec<_>>();
+                let Some(word) = options.normalize(fields[0])? else {
+                    return Ok(None);
+                };
+                Ok(Some((word, WordInfo::parse(&fields[1..])?)))
+            },
+            |(word, info)| {
+                metadata.entries.insert(word, info);
+            },
+        );
+        (metadata, report)
+    }
+
+    pub fn insert(&mut self, word: &str, info: WordInfo) {
+        self.entries.insert(word.to_string(), info);
+    }
+
+    pub fn get(&self, word: &str) -> Option<&WordInfo> {
+        self.entries.get(word)
+    }
+
+    /// Information about `word`, only if `lexicon` contains it.
+    pub fn lookup<L: Lexicon + ?Sized>(&self, lexicon: &L, word: &str) -> Option<&WordInfo> {
+        if !lexicon.contains(word) {
+            return None;
+        }
+        self.get(word)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WordInfo, WordMetadata};
+    use crate::word_tree::{
+        load_from,
+        normalize::{LoadOptions, RejectReason},
+    };
+
+    #[test]
+    fn sidecar() {
+        let sidecar = "# word\tpos\trank\tsources\tdefinition\n\
+            Hey\tinterjection\t812\tcollins, twl\tused to attract attention\r\n\
+            you\tpronoun\t\t\t\n\
+            yo\n\
+            bad\tadjective\tfirst\n\
+            you\tpronoun\t14\n";
+        let (metadata, report) =
+            WordMetadata::load_from(sidecar.as_bytes(), &LoadOptions::default());
+        assert_eq!(report.accepted, 4);
+        assert_eq!(report.skipped, 1);
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(report.rejected[0].line_number, 5);
+        assert_eq!(
+            report.rejected[0].reason,
+            RejectReason::InvalidField("frequency rank")
+        );
+        assert_eq!(metadata.len(), 3);
+
+        let hey = metadata.get("hey").unwrap();
+        assert_eq!(
+            *hey,
+            WordInfo {
+                part_of_speech: Some("interjection".to_string()),
+                frequency_rank: Some(812),
+                sources: vec!["collins".to_string(), "twl".to_string()],
+                definition: Some("used to attract attention".to_string()),
+            }
+        );
+        assert_eq!(
+            hey.to_string(),
+            "interjection, rank 812: used to attract attention (collins, twl)"
+        );
+        assert_eq!(metadata.get("you").unwrap().frequency_rank, Some(14));
+        assert_eq!(*metadata.get("yo").unwrap(), WordInfo::default());
+        assert_eq!(metadata.get("yo").unwrap().to_string(), "");
+    }
+
+    #[test]
+    fn lookup() {
+        let words = load_from("hey\n".as_bytes());
+        let mut metadata = WordMetadata::default();
+        let info = WordInfo {
+            definition: Some("hello".to_string()),
+            ..Default::default()
+        };
+        metadata.insert("hey", info.clone());
+        metadata.insert("yo", info.clone());
+        assert_eq!(metadata.lookup(&words, "hey"), Some(&info));
+        assert_eq!(metadata.lookup(&words, "yo"), None, "not a word");
+        assert_eq!(info.to_string(), "hello");
+    }
+}
@@ -0,0 +1,215 @@
+//! Cleanup of word list lines before they become dictionary entries.
+
+use std::{collections::BTreeSet, io::BufRead};
+
+use unicode_normalization::UnicodeNormalization;
+
+/// How lines of a word list are turned into words.
+///
+/// Line endings (`\n` and `\r\n`) are always removed.
+pub struct LoadOptions {
+    /// Removes leading and trailing whitespace.
+    pub trim: bool,
+    /// Lowercases words, so `HELLO` and `hello` are the same entry.
+    pub case_fold: bool,
+    /// Applies Unicode NFC, so a precomposed `é` and `e` followed by a combining accent
+    /// are the same letter.
+    pub nfc: bool,
+    /// Rejects words containing letters outside of this set, checked after the steps above.
+    pub alphabet: Option<BTreeSet<char>>,
+    /// Lines starting with this prefix (after trimming) are ignored.
+    pub comment_prefix: Option<String>,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            trim: true,
+            case_fold: true,
+            nfc: true,
+            alphabet: None,
+            comment_prefix: Some("#".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RejectReason {
+    InvalidUtf8,
+    /// The word contains whitespace, even after trimming.
+    Whitespace,
+    ForbiddenLetter(char),
+    /// A field after the word could not be parsed, by name.
+    InvalidField(&'static str),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RejectedLine {
+    /// 1-based.
+    pub line_number: usize,
+    /// Lossy UTF-8 content of the line, before normalization.
+    pub content: String,
+    pub reason: RejectReason,
+}
+
+/// What happened to each line of a word list.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct LoadReport {
+    /// Lines which became words, including duplicates.
+    pub accepted: usize,
+    /// Empty and comment lines.
+    pub skipped: usize,
+    pub rejected: Vec<RejectedLine>,
+}
+
+impl LoadOptions {
+    /// Returns the word of `line`, `Ok(None)` for empty and comment lines.
+    pub fn normalize(&self, line: &str) -> Result<Option<String>, RejectReason> {
+        let mut line = line.strip_suffix('\r').unwrap_or(line);
+        if self.trim {
+            line = line.trim();
+        }
+        if line.is_empty()
+            || self
+                .comment_prefix
+                .as_ref()
+                .is_some_and(|prefix| line.starts_with(prefix.as_str()))
+        {
+            return Ok(None);
+        }
+        let mut word = if self.case_fold {
+            line.to_lowercase()
+        } else {
+            line.to_string()
+        };
+        if self.nfc {
+            word = word.nfc().collect();
+        }
+        if word.chars().any(char::is_whitespace) {
+            return Err(RejectReason::Whitespace);
+        }
+        if let Some(alphabet) = &self.alphabet {
+            if let Some(letter) = word.chars().find(|l| !alphabet.contains(l)) {
+                return Err(RejectReason::ForbiddenLetter(letter));
+            }
+        }
+        Ok(Some(word))
+    }
+
+    /// Normalizes each line of `reader`, calling `on_word` for accepted words.
+    ///
+    /// Reading stops at the first I/O error, invalid UTF-8 only rejects its line.
+    pub fn read_words<B, F>(&self, reader: B, on_word: F) -> LoadReport
+    where
+        B: BufRead,
+        F: FnMut(String),
+    {
+        Self::read_entries(reader, |line| self.normalize(line), on_word)
+    }
+
+    /// Like [`LoadOptions::read_words`], for lines holding more than a word: `parse` turns
+    /// a line into an entry, `Ok(None)` for lines to skip.
+    pub fn read_entries<B, T, P, F>(reader: B, mut parse: P, mut on_entry: F) -> LoadReport
+    where
+        B: BufRead,
+        P: FnMut(&str) -> Result<Option<T>, RejectReason>,
+        F: FnMut(T),
+    {
+        let mut report = LoadReport::default();
+        for (i, line) in reader.split(b'\n').enumerate() {
+            let Ok(line) = line else {
+                break;
+            };
+            let result = match std::str::from_utf8(&line) {
+                Ok(line) => parse(line),
+                Err(_) => Err(RejectReason::InvalidUtf8),
+            };
+            match result {
+                Ok(Some(entry)) => {
+                    report.accepted += 1;
+                    on_entry(entry);
+                }
+                Ok(None) => report.skipped += 1,
+                Err(reason) => report.rejected.push(RejectedLine {
+                    line_number: i + 1,
+                    content: String::from_utf8_lossy(&line)
+                        .trim_end_matches('\r')
+                        .to_string(),
+                    reason,
+                }),
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LoadOptions, LoadReport, RejectReason, RejectedLine};
+
+    fn read(options: &LoadOptions, input: &[u8]) -> (Vec<String>, LoadReport) {
+        let mut words = vec![];
+        let report = options.read_words(input, |w| words.push(w));
+        (words, report)
+    }
+
+    #[test]
+    fn default_options() {
+        let (words, report) = read(
+            &LoadOptions::default(),
+            b"HELLO\r\n  world \n\n# comment\nCafe\xCC\x81\nice cream\n\xFF\xFEbad\nlast",
+        );
+        assert_eq!(words, vec!["hello", "world", "caf\u{e9}", "last"]);
+        assert_eq!(report.accepted, 4);
+        assert_eq!(report.skipped, 2);
+        assert_eq!(
+            report.rejected,
+            vec![
+                RejectedLine {
+                    line_number: 6,
+                    content: "ice cream".to_string(),
+                    reason: RejectReason::Whitespace,
+                },
+                RejectedLine {
+                    line_number: 7,
+                    content: "\u{fffd}\u{fffd}bad".to_string(),
+                    reason: RejectReason::InvalidUtf8,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn alphabet() {
+        let options = LoadOptions {
+            alphabet: Some(('a'..='z').collect()),
+            ..Default::default()
+        };
+        let (words, report) = read(&options, "abc\nd\u{e9}j\u{e0}\nxyz\n".as_bytes());
+        assert_eq!(words, vec!["abc", "xyz"]);
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(report.rejected[0].line_number, 2);
+        assert_eq!(
+            report.rejected[0].reason,
+            RejectReason::ForbiddenLetter('\u{e9}')
+        );
+    }
+
+    #[test]
+    fn disabled_steps() {
+        let options = LoadOptions {
+            trim: false,
+            case_fold: false,
+            nfc: false,
+            alphabet: None,
+            comment_prefix: None,
+        };
+        let (words, report) = read(&options, b"ABC\r\n#x\n e\xCC\x81\n");
+        assert_eq!(words, vec!["ABC", "#x"]);
+        assert_eq!(report.rejected[0].reason, RejectReason::Whitespace);
+        assert_eq!(
+            options.normalize("e\u{301}"),
+            Ok(Some("e\u{301}".to_string()))
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! House rules on top of a base dictionary, without editing it.
+
+use std::io::{BufRead, Write};
+
+use super::{
+    fuzzy::{self, Suggestion},
+    load_from_with,
+    normalize::{LoadOptions, LoadReport},
+    Lexicon, PossibleWords, WordTree,
+};
+
+/// Words added to (`allow`) and removed from (`deny`) a base dictionary.
+///
+/// A word in both lists is denied.
+#[derive(Clone, Default)]
+pub struct WordOverlay {
+    pub allow: WordTree,
+    pub deny: WordTree,
+}
+
+impl WordOverlay {
+    /// Loads both lists, see [`super::load_from_with`].
+    pub fn load_from<A: BufRead, D: BufRead>(
+        allow: A,
+        deny: D,
+        options: &LoadOptions,
+    ) -> (Self, LoadReport, LoadReport) {
+        let (allow, allow_report) = load_from_with(allow, options);
+        let (deny, deny_report) = load_from_with(deny, options);
+        let overlay = WordOverlay {
+            allow: allow.words_tree,
+            deny: deny.words_tree,
+        };
+        (overlay, allow_report, deny_report)
+    }
+
+    pub fn allow(&mut self, word: &str) {
+        self.allow.insert(word);
+    }
+
+    pub fn deny(&mut self, word: &str) {
+        self.deny.insert(word);
+    }
+
+    /// Removes `word` from the deny list, returns whether it was there.
+    pub fn undeny(&mut self, word: &str) -> bool {
+        self.deny.remove(word)
+    }
+
+    /// Writes the allow list, one word per line.
+    pub fn write_allow<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        write_words(&self.allow, writer)
+    }
+
+    /// Writes the deny list, one word per line.
+    pub fn write_deny<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        write_words(&self.deny, writer)
+    }
+}
+
+fn write_words<W: Write>(tree: &WordTree, mut writer: W) -> std::io::Result<()> {
+    for word in tree.words_with_prefix("") {
+        writeln!(writer, "{word}")?;
+    }
+    Ok(())
+}
+
+/// A dictionary seen through a [`WordOverlay`].
+pub struct Layered<'a, L: Lexicon + ?Sized> {
+    pub base: &'a L,
+    pub overlay: &'a WordOverlay,
+}
+
+impl<'a, L: Lexicon + ?Sized> Lexicon for Layered<'a, L> {
+    fn contains(&self, word: &str) -> bool {
+        !self.overlay.deny.contains(word)
+            && (self.overlay.allow.contains(word) || self.base.contains(word))
+    }
+
+    fn is_prefix(&self, prefix: &str) -> bool {
+        if !self.overlay.deny.is_prefix(prefix) {
+            return self.overlay.allow.is_prefix(prefix) || self.base.is_prefix(prefix);
+        }
+        // Denied words may be the only ones starting with `prefix`.
+        !self.words_with_prefix(prefix).is_empty()
+    }
+
+    fn words_with_prefix(&self, prefix: &str) -> Vec<String> {
+        let mut words = self.base.words_with_prefix(prefix);
+        words.extend(self.overlay.allow.words_with_prefix(prefix));
+        words.sort_unstable();
+        words.dedup();
+        words.retain(|word| !self.overlay.deny.contains(word));
+        words
+    }
+
+    fn suggestions(&self, word: &str, max_distance: usize) -> Vec<Suggestion> {
+        let mut suggestions = self.base.suggestions(word, max_distance);
+        suggestions.extend(self.overlay.allow.suggestions(word, max_distance));
+        suggestions.retain(|s| !self.overlay.deny.contains(&s.word));
+        fuzzy::rank(&mut suggestions);
+        suggestions
+    }
+}
+
+impl PossibleWords {
+    pub fn with_overlay<'a>(&'a self, overlay: &'a WordOverlay) -> Layered<'a, PossibleWords> {
+        Layered {
+            base: self,
+            overlay,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WordOverlay;
+    use crate::word_tree::{load_from, normalize::LoadOptions, Lexicon};
+
+    #[test]
+    fn layered_queries() {
+        let base = load_from("cat\ncar\ndog\ndoge\n".as_bytes());
+        let (mut overlay, _, deny_report) = WordOverlay::load_from(
+            "# house rules\ncarz\nzorg\n".as_bytes(),
+            "dog\ncat\ncarz\n".as_bytes(),
+            &LoadOptions::default(),
+        );
+        assert_eq!(deny_report.accepted, 3);
+        let layered = base.with_overlay(&overlay);
+
+        assert!(layered.contains("car"));
+        assert!(layered.contains("zorg"));
+        assert!(!layered.contains("cat"));
+        assert!(!layered.contains("dog"));
+        assert!(!layered.contains("carz"), "deny wins over allow");
+        assert!(layered.contains("doge"));
+
+        assert!(layered.is_prefix("zo"));
+        assert!(layered.is_prefix("do"));
+        assert!(!layered.is_prefix("cat"));
+        assert_eq!(layered.words_with_prefix(""), vec!["car", "doge", "zorg"]);
+
+        overlay.allow("cow");
+        let layered = base.with_overlay(&overlay);
+        assert!(layered.contains("cow"));
+        let suggestions = layered.suggestions("cor", 1);
+        assert_eq!(
+            suggestions
+                .iter()
+                .map(|s| s.word.as_str())
+                .collect::<Vec<_>>(),
+            vec!["car", "cow"]
+        );
+
+        assert!(overlay.undeny("dog"));
+        assert!(!overlay.undeny("dog"));
+        assert!(base.with_overlay(&overlay).contains("dog"));
+
+        let mut allow_file = vec![];
+        overlay.write_allow(&mut allow_file).unwrap();
+        assert_eq!(String::from_utf8(allow_file).unwrap(), "carz\ncow\nzorg\n");
+    }
+}
@@ -0,0 +1,351 @@
+//! Crossword-style queries: `h?l*o`, `[aeiou]??s`...
+
+use std::{collections::BTreeSet, str::FromStr};
+
+use super::{PossibleWords, WordTree};
+
+/// One position of a [`Pattern`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Token {
+    /// Exactly this letter.
+    Letter(char),
+    /// Any single letter (`?`).
+    Any,
+    /// A single letter from (or, if `negated`, not from) `letters` (`[abc]`, `[^a-e]`).
+    Class {
+        letters: BTreeSet<char>,
+        negated: bool,
+    },
+    /// Any number of letters, possibly none (`*`).
+    Gap,
+}
+
+impl Token {
+    fn matches(&self, letter: char) -> bool {
+        match self {
+            Token::Letter(expected) => *expected == letter,
+            Token::Any | Token::Gap => true,
+            Token::Class { letters, negated } => letters.contains(&letter) != *negated,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PatternError {
+    /// A `[` without its `]`.
+    UnclosedClass,
+    /// `[]` or `[^]`.
+    EmptyClass,
+    /// A range like `[z-a]`.
+    InvalidRange(char, char),
+}
+
+impl std::fmt::Display for PatternError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatternError::UnclosedClass => write!(f, "missing ']' in pattern"),
+            PatternError::EmptyClass => write!(f, "empty letter class in pattern"),
+            PatternError::InvalidRange(from, to) => write!(f, "invalid range {from}-{to}"),
+        }
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+/// A sequence of [`Token`]s, with bounds on the length (in letters) of matching words.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pattern {
+    pub tokens: Vec<Token>,
+    pub min_len: usize,
+    pub max_len: Option<usize>,
+}
+
+impl Pattern {
+    /// Parses `?` (any letter), `*` (any letters), `[abc]`, `[a-c]`, `[^abc]` (letter classes),
+    /// other characters stand for themselves.
+    pub fn parse(pattern: &str) -> Result<Self, PatternError> {
+        let mut tokens = vec![];
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            let token = match c {
+                '?' => Token::Any,
+                '*' => Token::Gap,
+                '[' => parse_class(&mut chars)?,
+                letter => Token::Letter(letter),
+            };
+            // Consecutive gaps match the same words as a single one.
+            if !(token == Token::Gap && tokens.last() == Some(&Token::Gap)) {
+                tokens.push(token);
+            }
+        }
+        Ok(Pattern {
+            tokens,
+            ..Default::default()
+        })
+    }
+
+    /// Restricts matches to words of `min_len` to `max_len` letters.
+    pub fn with_len(self, min_len: usize, max_len: Option<usize>) -> Self {
+        Pattern {
+            min_len,
+            max_len,
+            ..self
+        }
+    }
+
+    /// Returns true if `word` matches this pattern.
+    pub fn matches(&self, word: &str) -> bool {
+        let len = word.chars().count();
+        if len < self.min_len || self.max_len.is_some_and(|max| len > max) {
+            return false;
+        }
+        let mut states = self.start();
+        for letter in word.chars() {
+            states = self.step(&states, letter);
+            if states.is_empty() {
+                return false;
+            }
+        }
+        self.accepts(&states)
+    }
+
+    fn start(&self) -> Vec<usize> {
+        let mut states = vec![];
+        self.add_state(&mut states, 0);
+        states
+    }
+
+    /// Adds `state`, and the states after the gaps it starts.
+    fn add_state(&self, states: &mut Vec<usize>, mut state: usize) {
+        loop {
+            if !states.contains(&state) {
+                states.push(state);
+            }
+            if self.tokens.get(state) != Some(&Token::Gap) {
+                break;
+            }
+            state += 1;
+        }
+    }
+
+    /// States reached after reading `letter` from `states`.
+    fn step(&self, states: &[usize], letter: char) -> Vec<usize> {
+        let mut next = vec![];
+        for &state in states {
+            match self.tokens.get(state) {
+                Some(Token::Gap) => self.add_state(&mut next, state),
+                Some(token) if token.matches(letter) => self.add_state(&mut next, state + 1),
+                _ => {}
+            }
+        }
+        next
+    }
+
+    fn accepts(&self, states: &[usize]) -> bool {
+        states.contains(&self.tokens.len())
+    }
+}
+
+fn parse_class(chars: &mut std::str::Chars) -> Result<Token, PatternError> {
+    let mut letters = BTreeSet::new();
+    let mut negated = false;
+    let mut previous = None;
+    let mut first = true;
+    loop {
+        let c = chars.next().ok_or(PatternError::UnclosedClass)?;
+        match c {
+            ']' => break,
+            '^' if first => negated = true,
+            '-' if previous.is_some() && !chars.as_str().starts_with(']') => {
+                let from = previous.take().unwrap();
+                let to = chars.next().ok_or(PatternError::UnclosedClass)?;
+                if to < from {
+                    return Err(PatternError::InvalidRange(from, to));
+                }
+                letters.extend(from..=to);
+            }
+            letter => {
+                letters.insert(letter);
+                previous = Some(letter);
+            }
+        }
+        first = false;
+    }
+    if letters.is_empty() {
+        return Err(PatternError::EmptyClass);
+    }
+    Ok(Token::Class { letters, negated })
+}
+
+impl FromStr for Pattern {
+    type Err = PatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Pattern::parse(s)
+    }
+}
+
+struct Frame<'a> {
+    node: &'a WordTree,
+    /// Letter leading to `node`, `None` for the starting node.
+    letter: Option<char>,
+    depth: usize,
+    states: Vec<usize>,
+}
+
+/// Words matching a [`Pattern`], in lexicographic order, see [`WordTree::matching`].
+pub struct Matches<'a> {
+    pattern: &'a Pattern,
+    stack: Vec<Frame<'a>>,
+    word: String,
+}
+
+impl<'a> Iterator for Matches<'a> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        while let Some(frame) = self.stack.pop() {
+            // `word` holds the letters of the previous frame's branch, keep the common part.
+            while self.word.chars().count() > frame.depth.saturating_sub(1) {
+                self.word.pop();
+            }
+            if let Some(letter) = frame.letter {
+                self.word.push(letter);
+            }
+            if self.pattern.max_len.is_none_or(|max| frame.depth < max) {
+                for (letter, child) in frame.node.next.iter().rev() {
+                    let states = self.pattern.step(&frame.states, *letter);
+                    if !states.is_empty() {
+                        self.stack.push(Frame {
+                            node: child,
+                            letter: Some(*letter),
+                            depth: frame.depth + 1,
+                            states,
+                        });
+                    }
+                }
+            }
+            if frame.node.can_be_last_letter
+                && frame.depth >= self.pattern.min_len.max(1)
+                && self.pattern.accepts(&frame.states)
+            {
+                return Some(self.word.clone());
+            }
+        }
+        None
+    }
+}
+
+impl WordTree {
+    /// Iterates over the words of this tree matching `pattern`, in lexicographic order.
+    ///
+    /// Branches are pruned as soon as they can't match anymore.
+    pub fn matching<'a>(&'a self, pattern: &'a Pattern) -> Matches<'a> {
+        Matches {
+            pattern,
+            stack: vec![Frame {
+                node: self,
+                letter: None,
+                depth: 0,
+                states: pattern.start(),
+            }],
+            word: String::new(),
+        }
+    }
+}
+
+impl PossibleWords {
+    pub fn matching<'a>(&'a self, pattern: &'a Pattern) -> Matches<'a> {
+        self.words_tree.matching(pattern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::{Pattern, PatternError, Token};
+    use crate::word_tree::load_from;
+
+    const WORDS: &str = "hello\nhalo\nhallo\nhelo\nhells\nheros\nhero\nbees\nyes\ntees\n";
+
+    fn matching(pattern: &Pattern) -> Vec<String> {
+        load_from(WORDS.as_bytes()).matching(pattern).collect()
+    }
+
+    #[test]
+    fn parse() {
+        let pattern = Pattern::parse("a?*[b-d]**[^x]").unwrap();
+        assert_eq!(
+            pattern.tokens,
+            vec![
+                Token::Letter('a'),
+                Token::Any,
+                Token::Gap,
+                Token::Class {
+                    letters: BTreeSet::from(['b', 'c', 'd']),
+                    negated: false,
+                },
+                Token::Gap,
+                Token::Class {
+                    letters: BTreeSet::from(['x']),
+                    negated: true,
+                },
+            ]
+        );
+        assert_eq!(
+            "[a-]".parse::<Pattern>().unwrap().tokens,
+            vec![Token::Class {
+                letters: BTreeSet::from(['a', '-']),
+                negated: false,
+            }]
+        );
+        assert_eq!(Pattern::parse("a[bc"), Err(PatternError::UnclosedClass));
+        assert_eq!(Pattern::parse("[^]"), Err(PatternError::EmptyClass));
+        assert_eq!(
+            Pattern::parse("[z-a]"),
+            Err(PatternError::InvalidRange('z', 'a'))
+        );
+    }
+
+    #[test]
+    fn wildcards() {
+        let pattern = Pattern::parse("h?l*o").unwrap();
+        assert_eq!(matching(&pattern), vec!["hallo", "halo", "hello", "helo"]);
+        assert!(!pattern.matches("hlo"));
+        assert!(pattern.matches("hallo"));
+
+        let pattern = Pattern::parse("*").unwrap();
+        assert_eq!(matching(&pattern).len(), 10);
+        let pattern = Pattern::parse("*s").unwrap();
+        assert_eq!(
+            matching(&pattern),
+            vec!["bees", "hells", "heros", "tees", "yes"]
+        );
+        assert!(matching(&Pattern::parse("").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn classes_and_lengths() {
+        let pattern = Pattern::parse("[^h]e*s").unwrap();
+        assert_eq!(matching(&pattern), vec!["bees", "tees", "yes"]);
+
+        // 5 letters, the 2nd is 'e' and the last is 's'.
+        let pattern = Pattern::parse("?e*s").unwrap().with_len(5, Some(5));
+        assert_eq!(matching(&pattern), vec!["hells", "heros"]);
+        assert!(!pattern.matches("yes"));
+
+        let pattern = Pattern::parse("h*").unwrap().with_len(0, Some(4));
+        assert_eq!(matching(&pattern), vec!["halo", "helo", "hero"]);
+    }
+
+    #[test]
+    fn early_termination() {
+        let words = load_from(WORDS.as_bytes());
+        let pattern = Pattern::parse("h*").unwrap();
+        assert_eq!(
+            words.matching(&pattern).take(2).collect::<Vec<_>>(),
+            vec!["hallo", "halo"]
+        );
+    }
+}
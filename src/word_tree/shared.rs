@@ -0,0 +1,310 @@
+//! Dictionaries shared between threads, and built on several of them.
+//!
+//! Dictionaries are never modified once built: a [`SharedDictionary`] is a reference counted
+//! handle, so background tasks like a solver can query the game dictionary without copying it.
+
+use std::{
+    cmp::Reverse, collections::BTreeMap, io::BufRead, num::NonZeroUsize, ops::Deref, sync::Arc,
+    thread,
+};
+
+use super::{
+    binary::{FormatError, LoadedDictionary, MAGIC},
+    fuzzy::Suggestion,
+    normalize::{LoadOptions, LoadReport},
+    overlay::{Layered, WordOverlay},
+    Lexicon, PossibleWords, WordTree,
+};
+
+/// An immutable dictionary, cheap to clone and to send to other threads.
+#[derive(Clone)]
+pub struct SharedDictionary(Arc<LoadedDictionary>);
+
+impl SharedDictionary {
+    /// Whether both handles share the same dictionary.
+    pub fn ptr_eq(&self, other: &SharedDictionary) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Deref for SharedDictionary {
+    type Target = LoadedDictionary;
+
+    fn deref(&self) -> &LoadedDictionary {
+        &self.0
+    }
+}
+
+impl From<LoadedDictionary> for SharedDictionary {
+    fn from(value: LoadedDictionary) -> Self {
+        SharedDictionary(Arc::new(value))
+    }
+}
+
+impl From<PossibleWords> for SharedDictionary {
+    fn from(value: PossibleWords) -> Self {
+        LoadedDictionary::Words(value).into()
+    }
+}
+
+impl Lexicon for SharedDictionary {
+    fn contains(&self, word: &str) -> bool {
+        self.0.contains(word)
+    }
+
+    fn is_prefix(&self, prefix: &str) -> bool {
+        self.0.is_prefix(prefix)
+    }
+
+    fn words_with_prefix(&self, prefix: &str) -> Vec<String> {
+        self.0.words_with_prefix(prefix)
+    }
+
+    fn suggestions(&self, word: &str, max_distance: usize) -> Vec<Suggestion> {
+        self.0.suggestions(word, max_distance)
+    }
+}
+
+/// Owned counterpart of [`Layered`], for queries outside of the thread which owns the overlay.
+#[derive(Clone)]
+pub struct SharedLayered {
+    pub base: SharedDictionary,
+    pub overlay: Arc<WordOverlay>,
+}
+
+impl SharedLayered {
+    fn layered(&self) -> Layered<'_, SharedDictionary> {
+        Layered {
+            base: &self.base,
+            overlay: &self.overlay,
+        }
+    }
+}
+
+impl Lexicon for SharedLayered {
+    fn contains(&self, word: &str) -> bool {
+        self.layered().contains(word)
+    }
+
+    fn is_prefix(&self, prefix: &str) -> bool {
+        self.layered().is_prefix(prefix)
+    }
+
+    fn words_with_prefix(&self, prefix: &str) -> Vec<String> {
+        self.layered().words_with_prefix(prefix)
+    }
+
+    fn suggestions(&self, word: &str, max_distance: usize) -> Vec<Suggestion> {
+        self.layered().suggestions(word, max_distance)
+    }
+}
+
+impl PossibleWords {
+    /// Builds the same tree as inserting `words` one by one, on up to `threads` threads.
+    pub fn from_words_parallel(words: Vec<String>, threads: NonZeroUsize) -> PossibleWords {
+        PossibleWords::from_words_with(words, threads, run_on_threads)
+    }
+
+    /// Builds the same tree as inserting `words` one by one, from up to `workers` jobs which
+    /// `run_all` runs, e.g. on a task pool, returning their branches in any order.
+    ///
+    /// Words are partitioned by their first letter, each partition is a branch of the root
+    /// built by a single job.
+    pub fn from_words_with<R>(
+        words: Vec<String>,
+        workers: NonZeroUsize,
+        run_all: R,
+    ) -> PossibleWords
+    where
+        R: FnOnce(Vec<BranchJob>) -> Vec<Branches>,
+    {
+        let mut partitions = BTreeMap::<char, Vec<String>>::new();
+        for word in words {
+            if let Some(first) = word.chars().next() {
+                partitions.entry(first).or_default().push(word);
+            }
+        }
+        // Largest partitions first, each to the least busy worker.
+        let mut partitions = partitions.into_iter().collect::<Vec<_>>();
+        partitions.sort_by_key(|(_, words)| Reverse(words.len()));
+        let mut jobs = vec![(0, vec![]); workers.get().min(partitions.len())];
+        for partition in partitions {
+            if let Some((size, job)) = jobs.iter_mut().min_by_key(|(size, _)| *size) {
+                *size += partition.1.len();
+                job.push(partition);
+            }
+        }
+        let jobs = jobs
+            .into_iter()
+            .map(|(_, partitions)| BranchJob(partitions))
+            .collect();
+        PossibleWords {
+            words_tree: WordTree {
+                next: run_all(jobs)
+                    .into_iter()
+                    .flat_map(|branches| branches.0)
+                    .map(|(letter, branch)| (letter, Box::new(branch)))
+                    .collect(),
+                can_be_last_letter: false,
+            },
+        }
+    }
+}
+
+/// Partitions of the words of a tree, built into branches of its root by a single worker,
+/// see [`PossibleWords::from_words_with`].
+pub struct BranchJob(Vec<(char, Vec<String>)>);
+
+/// Branches of the root built by a [`BranchJob`].
+pub struct Branches(Vec<(char, WordTree)>);
+
+impl BranchJob {
+    pub fn run(self) -> Branches {
+        Branches(build_branches(self.0))
+    }
+}
+
+/// Runs each job on its own thread.
+fn run_on_threads(jobs: Vec<BranchJob>) -> Vec<Branches> {
+    thread::scope(|scope| {
+        let handles = jobs
+            .into_iter()
+            .map(|job| scope.spawn(|| job.run()))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("building a branch panicked"))
+            .collect()
+    })
+}
+
+/// Trees of the words of each partition, without their first letter.
+fn build_branches(partitions: Vec<(char, Vec<String>)>) -> Vec<(char, WordTree)> {
+    partitions
+        .into_iter()
+        .map(|(first, words)| {
+            let mut branch = WordTree::default();
+            for word in &words {
+                let rest = &word[first.len_utf8()..];
+                if rest.is_empty() {
+                    branch.can_be_last_letter = true;
+                } else {
+                    branch.insert(rest);
+                }
+            }
+            (first, branch)
+        })
+        .collect()
+}
+
+/// Same as [`super::load_from_with`], the tree is built on all available threads.
+pub fn load_from_parallel<B: BufRead>(
+    reader: B,
+    options: &LoadOptions,
+) -> (PossibleWords, LoadReport) {
+    let mut words = vec![];
+    let report = options.read_words(reader, |word| words.push(word));
+    let threads = thread::available_parallelism().unwrap_or(NonZeroUsize::MIN);
+    (PossibleWords::from_words_parallel(words, threads), report)
+}
+
+impl LoadedDictionary {
+    /// Same as [`LoadedDictionary::from_bytes_with`], word lists are built with
+    /// [`load_from_parallel`].
+    pub fn from_bytes_parallel(
+        bytes: Vec<u8>,
+        options: &LoadOptions,
+    ) -> Result<(Self, LoadReport), FormatError> {
+        let threads = thread::available_parallelism().unwrap_or(NonZeroUsize::MIN);
+        Self::from_bytes_parallel_with(bytes, options, threads, run_on_threads)
+    }
+
+    /// Same as [`LoadedDictionary::from_bytes_parallel`], word lists are built by `run_all`,
+    /// see [`PossibleWords::from_words_with`].
+    pub fn from_bytes_parallel_with<R>(
+        bytes: Vec<u8>,
+        options: &LoadOptions,
+        workers: NonZeroUsize,
+        run_all: R,
+    ) -> Result<(Self, LoadReport), FormatError>
+    where
+        R: FnOnce(Vec<BranchJob>) -> Vec<Branches>,
+    {
+        if bytes.starts_with(&MAGIC) {
+            return Self::from_bytes_with(bytes, options);
+        }
+        let mut words = vec![];
+        let report = options.read_words(bytes.as_slice(), |word| words.push(word));
+        let words = PossibleWords::from_words_with(words, workers, run_all);
+        Ok((LoadedDictionary::Words(words), report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroUsize, sync::Arc, thread};
+
+    use super::{load_from_parallel, BranchJob, SharedDictionary, SharedLayered};
+    use crate::word_tree::{
+        binary::{self, LoadedDictionary},
+        load_from,
+        normalize::LoadOptions,
+        overlay::WordOverlay,
+        Lexicon, PossibleWords,
+    };
+
+    const WORDS: &str = "cat\ncats\ndog\nbird\na\nzebra\n\u{e9}t\u{e9}\ndo\n";
+
+    #[test]
+    fn parallel_build() {
+        let expected = load_from(WORDS.as_bytes());
+        let words = expected.words().collect::<Vec<String>>();
+        for threads in [1, 2, 3, 16] {
+            let threads = NonZeroUsize::new(threads).unwrap();
+            let tree = PossibleWords::from_words_parallel(words.clone(), threads);
+            assert!(tree.words_tree == expected.words_tree);
+        }
+        let (tree, report) = load_from_parallel(WORDS.as_bytes(), &LoadOptions::default());
+        assert!(tree.words_tree == expected.words_tree);
+        assert_eq!(report.accepted, 8);
+        let tree = PossibleWords::from_words_with(words.clone(), NonZeroUsize::MAX, |jobs| {
+            jobs.into_iter().rev().map(BranchJob::run).collect()
+        });
+        assert!(tree.words_tree == expected.words_tree);
+        let empty = PossibleWords::from_words_parallel(vec![], NonZeroUsize::MIN);
+        assert!(empty.words_with_prefix("").is_empty());
+
+        let compiled = binary::to_bytes(&expected);
+        for bytes in [compiled, WORDS.as_bytes().to_vec()] {
+            let (dictionary, _) =
+                LoadedDictionary::from_bytes_parallel(bytes, &LoadOptions::default()).unwrap();
+            assert_eq!(dictionary.words_with_prefix(""), words);
+        }
+    }
+
+    fn assert_send_sync<T: Send + Sync + Clone + 'static>() {}
+
+    #[test]
+    fn shared_between_threads() {
+        assert_send_sync::<SharedDictionary>();
+        assert_send_sync::<SharedLayered>();
+
+        let dictionary = SharedDictionary::from(load_from(WORDS.as_bytes()));
+        assert!(dictionary.ptr_eq(&dictionary.clone()));
+        assert!(!dictionary.ptr_eq(&SharedDictionary::from(load_from(WORDS.as_bytes()))));
+        let mut overlay = WordOverlay::default();
+        overlay.allow("cow");
+        overlay.deny("cat");
+        let layered = SharedLayered {
+            base: dictionary.clone(),
+            overlay: Arc::new(overlay),
+        };
+        let handles = ["cat", "cow", "dog"].map(|word| {
+            let dictionary = dictionary.clone();
+            let layered = layered.clone();
+            thread::spawn(move || (dictionary.contains(word), layered.contains(word)))
+        });
+        let results = handles.map(|handle| handle.join().unwrap());
+        assert_eq!(results, [(true, false), (false, true), (true, true)]);
+    }
+}
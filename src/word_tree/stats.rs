@@ -0,0 +1,231 @@
+//! Corpus statistics, to tune tile distributions.
+
+use std::{collections::BTreeMap, fmt};
+
+use super::{PossibleWords, WordTree};
+
+/// Shape of a [`WordTree`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NodeStats {
+    pub node_count: usize,
+    /// Nodes without children.
+    pub leaf_count: usize,
+    /// Letters of the longest branch.
+    pub max_depth: usize,
+    /// Number of nodes by number of children.
+    pub branching: BTreeMap<usize, usize>,
+}
+
+impl NodeStats {
+    /// Average number of children of the nodes which have some.
+    pub fn average_branching(&self) -> f32 {
+        let inner = self.node_count - self.leaf_count;
+        if inner == 0 {
+            return 0.0;
+        }
+        // Every node but the root is the child of another one.
+        (self.node_count - 1) as f32 / inner as f32
+    }
+
+    fn add(&mut self, node: &WordTree, depth: usize) {
+        self.node_count += 1;
+        if node.next.is_empty() {
+            self.leaf_count += 1;
+        }
+        self.max_depth = self.max_depth.max(depth);
+        *self.branching.entry(node.next.len()).or_default() += 1;
+        for child in node.next.values() {
+            self.add(child, depth + 1);
+        }
+    }
+}
+
+/// Letter and length distributions of a dictionary, each word counted once.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub word_count: usize,
+    /// Number of words by length, in letters.
+    pub lengths: BTreeMap<usize, usize>,
+    /// Occurrences of each letter.
+    pub letters: BTreeMap<char, usize>,
+    /// Occurrences of each letter, by position in the word (0 is the first letter).
+    pub positions: Vec<BTreeMap<char, usize>>,
+    /// Occurrences of each pair of consecutive letters.
+    pub bigrams: BTreeMap<(char, char), usize>,
+    pub nodes: NodeStats,
+}
+
+impl Stats {
+    pub fn new(tree: &WordTree) -> Self {
+        let mut stats = Stats::default();
+        tree.visit(&mut |letters| stats.add_word(letters));
+        stats.nodes.add(tree, 0);
+        stats
+    }
+
+    fn add_word(&mut self, letters: &[char]) {
+        self.word_count += 1;
+        *self.lengths.entry(letters.len()).or_default() += 1;
+        if self.positions.len() < letters.len() {
+            self.positions.resize_with(letters.len(), Default::default);
+        }
+        for (position, letter) in letters.iter().enumerate() {
+            *self.letters.entry(*letter).or_default() += 1;
+            *self.positions[position].entry(*letter).or_default() += 1;
+        }
+        for pair in letters.windows(2) {
+            *self.bigrams.entry((pair[0], pair[1])).or_default() += 1;
+        }
+    }
+
+    pub fn letter_count(&self) -> usize {
+        self.letters.values().sum()
+    }
+
+    /// Share of `letter` among all letters, from 0 to 1.
+    pub fn letter_frequency(&self, letter: char) -> f32 {
+        let total = self.letter_count();
+        if total == 0 {
+            return 0.0;
+        }
+        self.letters.get(&letter).copied().unwrap_or_default() as f32 / total as f32
+    }
+
+    pub fn average_length(&self) -> f32 {
+        if self.word_count == 0 {
+            return 0.0;
+        }
+        self.letter_count() as f32 / self.word_count as f32
+    }
+
+    /// The `count` most frequent bigrams, most frequent first.
+    pub fn top_bigrams(&self, count: usize) -> Vec<((char, char), usize)> {
+        let mut bigrams = self
+            .bigrams
+            .iter()
+            .map(|(pair, n)| (*pair, *n))
+            .collect::<Vec<_>>();
+        bigrams.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        bigrams.truncate(count);
+        bigrams
+    }
+}
+
+impl PossibleWords {
+    pub fn stats(&self) -> Stats {
+        Stats::new(&self.words_tree)
+    }
+}
+
+/// Human readable report, as printed by the `stats` command.
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} words, {} letters, {:.2} letters per word",
+            self.word_count,
+            self.letter_count(),
+            self.average_length()
+        )?;
+
+        writeln!(f, "\nlengths:")?;
+        for (length, count) in &self.lengths {
+            writeln!(f, "  {length:>3} {count:>8}")?;
+        }
+
+        writeln!(f, "\nletters:")?;
+        let mut letters = self.letters.iter().collect::<Vec<_>>();
+        letters.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        for (letter, count) in letters {
+            writeln!(
+                f,
+                "  {letter} {count:>8} {:>6.2}%",
+                100.0 * self.letter_frequency(*letter)
+            )?;
+        }
+
+        writeln!(f, "\nmost frequent letter by position:")?;
+        for (position, letters) in self.positions.iter().enumerate() {
+            let total = letters.values().sum::<usize>();
+            if let Some((letter, count)) = letters
+                .iter()
+                .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+            {
+                writeln!(
+                    f,
+                    "  {:>3} {letter} {:>6.2}%",
+                    position + 1,
+                    100.0 * *count as f32 / total as f32
+                )?;
+            }
+        }
+
+        writeln!(f, "\nmost frequent bigrams:")?;
+        for ((first, second), count) in self.top_bigrams(20) {
+            writeln!(f, "  {first}{second} {count:>8}")?;
+        }
+
+        writeln!(
+            f,
+            "\n{} nodes, {} leaves, depth {}, {:.2} children per inner node",
+            self.nodes.node_count,
+            self.nodes.leaf_count,
+            self.nodes.max_depth,
+            self.nodes.average_branching()
+        )?;
+        writeln!(f, "nodes by number of children:")?;
+        for (children, count) in &self.nodes.branching {
+            writeln!(f, "  {children:>3} {count:>8}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::word_tree::load_from;
+
+    #[test]
+    fn stats() {
+        let stats = load_from("cat\ncar\ncats\na\n".as_bytes()).stats();
+        assert_eq!(stats.word_count, 4);
+        assert_eq!(stats.lengths, BTreeMap::from([(1, 1), (3, 2), (4, 1)]));
+        assert_eq!(
+            stats.letters,
+            BTreeMap::from([('a', 4), ('c', 3), ('r', 1), ('s', 1), ('t', 2)])
+        );
+        assert_eq!(stats.letter_count(), 11);
+        assert_eq!(stats.letter_frequency('a'), 4.0 / 11.0);
+        assert_eq!(stats.letter_frequency('z'), 0.0);
+        assert_eq!(stats.positions.len(), 4);
+        assert_eq!(stats.positions[0], BTreeMap::from([('a', 1), ('c', 3)]));
+        assert_eq!(stats.positions[3], BTreeMap::from([('s', 1)]));
+        assert_eq!(stats.top_bigrams(2), vec![(('c', 'a'), 3), (('a', 't'), 2)]);
+
+        // root -> a, c -> a -> r, t -> s
+        assert_eq!(stats.nodes.node_count, 7);
+        assert_eq!(stats.nodes.leaf_count, 3);
+        assert_eq!(stats.nodes.max_depth, 4);
+        assert_eq!(
+            stats.nodes.branching,
+            BTreeMap::from([(0, 3), (1, 2), (2, 2)])
+        );
+        assert_eq!(stats.nodes.average_branching(), 6.0 / 4.0);
+
+        let report = stats.to_string();
+        assert!(report.starts_with("4 words, 11 letters, 2.75 letters per word\n"));
+        assert!(report.contains("  ca        3\n"));
+    }
+
+    #[test]
+    fn empty() {
+        let stats = load_from("".as_bytes()).stats();
+        assert_eq!(stats.word_count, 0);
+        assert_eq!(stats.average_length(), 0.0);
+        assert_eq!(stats.nodes.node_count, 1);
+        assert_eq!(stats.nodes.average_branching(), 0.0);
+        stats.to_string();
+    }
+}